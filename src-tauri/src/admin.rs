@@ -0,0 +1,138 @@
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info};
+
+use xcontrol_lib::config::AdminServerConfig;
+use xcontrol_lib::logs::LogStore;
+use xcontrol_lib::metrics;
+
+use crate::{
+    collect_health_summary, collect_service_metrics, collect_services_status, ProcessManager,
+};
+
+/// 管理服务器共享状态，与前端命令使用同一份内存数据
+#[derive(Clone)]
+struct AdminState {
+    process_manager: ProcessManager,
+    log_store: LogStore,
+}
+
+#[derive(Deserialize)]
+struct LogStreamQuery {
+    service: Option<String>,
+}
+
+/// 启动只读管理服务器，绑定失败时仅记录错误，不影响服务启动
+pub async fn serve(
+    config: AdminServerConfig,
+    process_manager: ProcessManager,
+    log_store: LogStore,
+) {
+    let state = AdminState {
+        process_manager,
+        log_store,
+    };
+
+    let app = Router::new()
+        .route("/services", get(list_services))
+        .route("/services/{name}/logs", get(service_logs))
+        .route("/ws/logs", get(stream_logs))
+        .route("/metrics", get(service_metrics))
+        .route("/healthz", get(health_summary))
+        .with_state(state);
+
+    let listener = match tokio::net::TcpListener::bind(&config.bind).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("管理服务器绑定 {} 失败: {}", config.bind, e);
+            return;
+        }
+    };
+
+    info!("管理服务器已启动: http://{}", config.bind);
+
+    if let Err(e) = axum::serve(listener, app).await {
+        error!("管理服务器异常退出: {}", e);
+    }
+}
+
+/// GET /services - 所有服务的当前状态
+async fn list_services(State(state): State<AdminState>) -> Response {
+    Json(collect_services_status(&state.process_manager)).into_response()
+}
+
+/// GET /services/{name}/logs - 指定服务最近缓存的日志
+async fn service_logs(Path(name): Path<String>, State(state): State<AdminState>) -> Response {
+    match state.log_store.recent(&name) {
+        Some(lines) => Json(lines).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("未找到服务 {} 的日志", name)).into_response(),
+    }
+}
+
+/// GET /metrics - Prometheus 文本格式的服务指标
+async fn service_metrics(State(state): State<AdminState>) -> Response {
+    let body = metrics::render(&collect_service_metrics(&state.process_manager));
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    )
+        .into_response()
+}
+
+/// GET /healthz - 汇总健康状态，全部就绪时返回 200，否则返回 503
+async fn health_summary(State(state): State<AdminState>) -> Response {
+    let summary = collect_health_summary(&state.process_manager);
+    let status = if summary.all_ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(summary)).into_response()
+}
+
+/// GET /ws/logs?service=<name> - 实时日志流，不指定 service 时推送全部服务
+async fn stream_logs(
+    ws: WebSocketUpgrade,
+    Query(query): Query<LogStreamQuery>,
+    State(state): State<AdminState>,
+) -> Response {
+    ws.on_upgrade(move |socket| forward_logs(socket, state.log_store, query.service))
+}
+
+async fn forward_logs(mut socket: WebSocket, log_store: LogStore, service: Option<String>) {
+    let mut receiver = log_store.subscribe();
+
+    loop {
+        let line = match receiver.recv().await {
+            Ok(line) => line,
+            // 客户端消费过慢时跳过积压的日志，继续推送最新内容
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+
+        if service
+            .as_ref()
+            .is_some_and(|name| name != &line.service_name)
+        {
+            continue;
+        }
+
+        let text = match serde_json::to_string(&line) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+
+        if socket.send(Message::Text(text.into())).await.is_err() {
+            break;
+        }
+    }
+}
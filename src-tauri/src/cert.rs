@@ -0,0 +1,145 @@
+/// 读取一个 DER 编码的 TLV，返回标签、内容与剩余数据
+fn read_tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&first, mut input) = input.split_first()?;
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || input.len() < count {
+            return None;
+        }
+        let (bytes, rest) = input.split_at(count);
+        input = rest;
+        bytes
+            .iter()
+            .fold(0usize, |len, &byte| (len << 8) | byte as usize)
+    };
+    if input.len() < len {
+        return None;
+    }
+    let (content, rest) = input.split_at(len);
+    Some((tag, content, rest))
+}
+
+/// 由公历日期计算自 1970-01-01 起的天数
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// 解析 UTCTime（YYMMDDHHMMSSZ）或 GeneralizedTime（YYYYMMDDHHMMSSZ），返回 Unix 时间戳（秒）
+fn parse_time(tag: u8, content: &[u8]) -> Option<i64> {
+    let text = std::str::from_utf8(content).ok()?.strip_suffix('Z')?;
+    let (year, rest) = match tag {
+        0x17 => {
+            let year: i64 = text.get(..2)?.parse().ok()?;
+            // RFC 5280：两位年份 50 及以上表示 19xx
+            (
+                if year >= 50 { 1900 + year } else { 2000 + year },
+                &text[2..],
+            )
+        }
+        0x18 => (text.get(..4)?.parse().ok()?, &text[4..]),
+        _ => return None,
+    };
+    if rest.len() != 10 || !rest.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let field = |i: usize| rest[i..i + 2].parse::<i64>().ok();
+    let days = days_from_civil(year, field(0)?, field(2)?);
+    Some(days * 86_400 + field(4)? * 3600 + field(6)? * 60 + field(8)?)
+}
+
+/// 读取 DER 编码证书的 notAfter（Unix 时间戳，秒），无法解析时返回 None
+pub fn not_after(der: &[u8]) -> Option<i64> {
+    let (0x30, certificate, _) = read_tlv(der)? else {
+        return None;
+    };
+    let (0x30, tbs, _) = read_tlv(certificate)? else {
+        return None;
+    };
+    // 可选的 [0] version，随后依次为 serialNumber、signature、issuer、validity
+    let mut rest = tbs;
+    if rest.first() == Some(&0xa0) {
+        rest = read_tlv(rest)?.2;
+    }
+    for _ in 0..3 {
+        rest = read_tlv(rest)?.2;
+    }
+    let (0x30, validity, _) = read_tlv(rest)? else {
+        return None;
+    };
+    let (_, _, validity) = read_tlv(validity)?;
+    let (tag, content, _) = read_tlv(validity)?;
+    parse_time(tag, content)
+}
+
+/// 证书距过期的剩余整天数，已过期时为负数
+pub fn days_remaining(der: &[u8], now_secs: i64) -> Option<i64> {
+    not_after(der).map(|not_after| (not_after - now_secs).div_euclid(86_400))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        if content.len() < 0x80 {
+            out.push(content.len() as u8);
+        } else {
+            out.push(0x82);
+            out.extend((content.len() as u16).to_be_bytes());
+        }
+        out.extend(content);
+        out
+    }
+
+    fn certificate(not_after: Vec<u8>) -> Vec<u8> {
+        let validity = [tlv(0x17, b"250101000000Z"), not_after].concat();
+        let tbs = [
+            tlv(0xa0, &tlv(0x02, &[2])),
+            tlv(0x02, &[1]),
+            tlv(0x30, &[]),
+            tlv(0x30, &tlv(0x31, &[0; 200])), // 长度超过 127，使用长格式
+            tlv(0x30, &validity),
+        ]
+        .concat();
+        tlv(0x30, &[tlv(0x30, &tbs), tlv(0x30, &[])].concat())
+    }
+
+    #[test]
+    fn reads_not_after_in_both_time_formats() {
+        // 2030-06-15 12:00:00 UTC
+        let expected = 1_907_755_200;
+        let utc = certificate(tlv(0x17, b"300615120000Z"));
+        assert_eq!(not_after(&utc), Some(expected));
+        let generalized = certificate(tlv(0x18, b"20300615120000Z"));
+        assert_eq!(not_after(&generalized), Some(expected));
+        assert_eq!(
+            not_after(&certificate(tlv(0x17, b"990101000000Z"))),
+            Some(915_148_800)
+        );
+    }
+
+    #[test]
+    fn days_remaining_rounds_down() {
+        let der = certificate(tlv(0x17, b"300615120000Z"));
+        let not_after = 1_907_755_200;
+        assert_eq!(days_remaining(&der, not_after - 86_400 * 10 + 1), Some(9));
+        assert_eq!(days_remaining(&der, not_after + 1), Some(-1));
+    }
+
+    #[test]
+    fn malformed_certificate_is_rejected() {
+        assert_eq!(not_after(&[]), None);
+        assert_eq!(not_after(&[0x30, 0x05, 0x30]), None);
+        assert_eq!(not_after(&certificate(tlv(0x17, b"3006151200Z"))), None);
+    }
+}
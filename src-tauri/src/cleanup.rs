@@ -0,0 +1,189 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::config::resolve_relative_to;
+
+/// Chromium 内核 webview 注册的窗口类，进程异常退出后残留会导致再次启动失败
+pub const CHROMIUM_WINDOW_CLASS: &str = "Chrome_WidgetWin_0";
+
+/// 一项清理操作，用于清除上次运行遗留、会阻止再次启动的资源
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum CleanupAction {
+    /// 注销窗口类，仅 Windows 生效，其他平台忽略
+    UnregisterWindowClass { class_name: String },
+    /// 删除文件（如锁文件、PID 文件）
+    RemoveFile { path: String },
+    /// 删除 Unix 域套接字文件，路径存在但不是套接字时不删除
+    RemoveSocket { path: String },
+}
+
+impl CleanupAction {
+    pub fn chromium_window_class() -> Self {
+        CleanupAction::UnregisterWindowClass {
+            class_name: CHROMIUM_WINDOW_CLASS.to_string(),
+        }
+    }
+
+    /// 相对路径以 base 为基准
+    pub fn resolve_relative_to(&mut self, base: &Path) {
+        match self {
+            CleanupAction::RemoveFile { path } | CleanupAction::RemoveSocket { path } => {
+                *path = resolve_relative_to(base, path);
+            }
+            CleanupAction::UnregisterWindowClass { .. } => {}
+        }
+    }
+
+    /// 执行清理，相对路径以 base_dir 为基准；返回是否确实清理了资源，资源不存在不算错误
+    pub fn run(&self, base_dir: &Path) -> Result<bool, String> {
+        match self {
+            CleanupAction::UnregisterWindowClass { class_name } => {
+                unregister_window_class(class_name)
+            }
+            CleanupAction::RemoveFile { path } => {
+                let path = base_dir.join(path);
+                match std::fs::remove_file(&path) {
+                    Ok(()) => Ok(true),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+                    Err(e) => Err(format!("删除文件 {:?} 失败: {}", path, e)),
+                }
+            }
+            CleanupAction::RemoveSocket { path } => {
+                let path = base_dir.join(path);
+                let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+                    return Ok(false);
+                };
+                if !is_socket(&metadata) {
+                    return Err(format!("{:?} 不是套接字文件，未删除", path));
+                }
+                std::fs::remove_file(&path)
+                    .map(|()| true)
+                    .map_err(|e| format!("删除套接字 {:?} 失败: {}", path, e))
+            }
+        }
+    }
+}
+
+/// 依次执行清理操作，失败只记录，不影响后续操作
+pub fn run_all(actions: &[CleanupAction], base_dir: &Path, context: &str) {
+    for action in actions {
+        match action.run(base_dir) {
+            Ok(true) => info!("{}: 已完成清理 {:?}", context, action),
+            Ok(false) => {}
+            Err(e) => warn!("{}: {}", context, e),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_socket(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    metadata.file_type().is_socket()
+}
+
+/// Windows 上的 Unix 域套接字以普通文件形式出现，无法区分，只排除目录
+#[cfg(not(unix))]
+fn is_socket(metadata: &std::fs::Metadata) -> bool {
+    !metadata.is_dir()
+}
+
+#[cfg(windows)]
+fn unregister_window_class(class_name: &str) -> Result<bool, String> {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr::null_mut;
+    use winapi::um::errhandlingapi::GetLastError;
+    use winapi::um::winuser::{GetClassInfoW, UnregisterClassW};
+
+    /// 类仍有打开的窗口
+    const ERROR_CLASS_HAS_WINDOWS: u32 = 1412;
+
+    let wide_name = OsStr::new(class_name)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<_>>();
+    unsafe {
+        let mut wnd_class = std::mem::zeroed();
+        if GetClassInfoW(null_mut(), wide_name.as_ptr(), &mut wnd_class) == 0 {
+            return Ok(false);
+        }
+        if UnregisterClassW(wide_name.as_ptr(), null_mut()) != 0 {
+            return Ok(true);
+        }
+        match GetLastError() {
+            ERROR_CLASS_HAS_WINDOWS => Ok(false),
+            err => Err(format!("注销窗口类 {} 失败，错误码: {}", class_name, err)),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn unregister_window_class(_class_name: &str) -> Result<bool, String> {
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_actions_and_resolves_paths() {
+        let actions: Vec<CleanupAction> = serde_json::from_str(
+            r#"[
+                {"action": "unregister_window_class", "class_name": "Chrome_WidgetWin_0"},
+                {"action": "remove_file", "path": "run/app.lock"},
+                {"action": "remove_socket", "path": "/tmp/app.sock"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(actions[0], CleanupAction::chromium_window_class());
+
+        let base = std::env::temp_dir();
+        let mut lock = actions[1].clone();
+        lock.resolve_relative_to(&base);
+        assert_eq!(
+            lock,
+            CleanupAction::RemoveFile {
+                path: resolve_relative_to(&base, "run/app.lock")
+            }
+        );
+        let mut socket = actions[2].clone();
+        socket.resolve_relative_to(&base);
+        assert_eq!(socket, actions[2]);
+        assert!(serde_json::from_str::<CleanupAction>(r#"{"action": "reboot"}"#).is_err());
+    }
+
+    #[test]
+    fn removes_stale_files_and_only_real_sockets() {
+        let dir = std::env::temp_dir().join(format!("xcontrol-cleanup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("app.lock"), "1234").unwrap();
+
+        let lock = CleanupAction::RemoveFile {
+            path: "app.lock".to_string(),
+        };
+        assert_eq!(lock.run(&dir), Ok(true));
+        assert!(!dir.join("app.lock").exists());
+        assert_eq!(lock.run(&dir), Ok(false));
+
+        let socket = CleanupAction::RemoveSocket {
+            path: "app.sock".to_string(),
+        };
+        assert_eq!(socket.run(&dir), Ok(false));
+        #[cfg(unix)]
+        {
+            std::fs::write(dir.join("app.sock"), "").unwrap();
+            assert!(socket.run(&dir).is_err());
+            std::fs::remove_file(dir.join("app.sock")).unwrap();
+            let listener = std::os::unix::net::UnixListener::bind(dir.join("app.sock")).unwrap();
+            drop(listener);
+            assert_eq!(socket.run(&dir), Ok(true));
+            assert!(!dir.join("app.sock").exists());
+        }
+        assert_eq!(CleanupAction::chromium_window_class().run(&dir), Ok(false));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
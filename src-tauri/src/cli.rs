@@ -0,0 +1,102 @@
+// 命令行参数与进程退出码
+//
+// 退出码仅在 --dry-run / --verify-only 模式下有意义，正常 GUI 模式始终以 0 退出：
+//   0 - 所有关键服务均已就绪（dry-run 下为配置与可执行文件检查通过，--list 下为配置有效）
+//   2 - 配置错误（配置文件缺失、解析失败或字段无效）
+//   3 - 关键服务启动失败（可执行文件不存在或进程无法创建）
+//   4 - 关键服务健康检查失败
+//   5 - 超过 global_startup_timeout_ms，启动已中止
+// 多个关键服务失败时返回最先发生的失败对应的退出码。
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_CONFIG_ERROR: i32 = 2;
+pub const EXIT_SPAWN_FAILURE: i32 = 3;
+pub const EXIT_HEALTH_FAILURE: i32 = 4;
+pub const EXIT_STARTUP_TIMEOUT: i32 = 5;
+
+#[derive(Default, Clone, Copy)]
+pub struct CliOptions {
+    /// 仅校验配置并打印启动计划，不启动任何进程
+    pub dry_run: bool,
+    /// 启动并检查所有服务，输出报告后退出
+    pub verify_only: bool,
+    /// 打印配置中的服务列表后退出
+    pub list: bool,
+    /// 打印版本号后退出
+    pub version: bool,
+    /// 打印用法后退出
+    pub help: bool,
+    /// 开发模式：服务 watch 中的文件变化时自动重启该服务，仅调试构建生效
+    pub watch: bool,
+    /// 向标准输出打印配置文件的 JSON Schema 后退出
+    pub dump_schema: bool,
+    /// 关键服务全部就绪后退出 xcontrol，服务继续独立运行
+    pub detach: bool,
+}
+
+impl CliOptions {
+    pub fn from_args() -> Self {
+        let mut options = Self::default();
+        for arg in std::env::args().skip(1) {
+            match arg.as_str() {
+                "--dry-run" => options.dry_run = true,
+                "--verify-only" => options.verify_only = true,
+                "--list" | "--list-services" => options.list = true,
+                "--version" | "-V" => options.version = true,
+                "--help" | "-h" => options.help = true,
+                "--watch" => options.watch = true,
+                "--dump-schema" => options.dump_schema = true,
+                "--detach" => options.detach = true,
+                _ => {}
+            }
+        }
+        options
+    }
+
+    /// 是否为需要向终端输出的命令行模式
+    pub fn uses_console(&self) -> bool {
+        self.dry_run
+            || self.verify_only
+            || self.list
+            || self.version
+            || self.help
+            || self.dump_schema
+            || self.detach
+    }
+}
+
+pub fn print_version() {
+    println!("xcontrol {}", env!("CARGO_PKG_VERSION"));
+}
+
+pub fn print_help() {
+    print_version();
+    println!();
+    println!("用法: xcontrol [选项]");
+    println!();
+    println!("不带选项时打开窗口并按 services.dat 启动所有服务。");
+    println!();
+    println!("选项:");
+    println!("  --dry-run        校验配置并打印启动计划，不启动任何进程");
+    println!("  --list           打印配置中的服务列表后退出");
+    println!("  --verify-only    启动并检查所有服务，输出报告后退出");
+    println!("  --watch          开发模式：服务 watch 中的文件变化时自动重启该服务（仅调试构建）");
+    println!("  --detach         关键服务全部就绪后退出，服务继续独立运行");
+    println!("  --dump-schema    打印 services.dat 的 JSON Schema 后退出");
+    println!("  -V, --version    打印版本号后退出");
+    println!("  -h, --help       打印本帮助后退出");
+}
+
+/// 发布构建没有控制台，从终端运行命令行模式时附加到父进程的控制台以便输出；
+/// 不是从终端启动时（如双击）没有可附加的控制台，输出被丢弃
+#[cfg(windows)]
+pub fn attach_parent_console() {
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // 已有控制台（调试构建）或不是从终端启动时会失败，忽略即可
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn attach_parent_console() {}
@@ -0,0 +1,95 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::warn;
+
+/// 服务启动条件，配置的条件需全部满足才会启动
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct ServiceCondition {
+    #[serde(default)]
+    pub file_exists: Option<String>, // 相对路径以服务工作目录为基准
+    #[serde(default)]
+    pub registry_value: Option<RegistryValueCondition>, // 仅 Windows
+}
+
+/// 注册表值存在性条件
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct RegistryValueCondition {
+    pub hive: String, // "HKLM"、"HKCU"、"HKCR"、"HKU" 或完整名称
+    pub key: String,
+    pub name: String,
+}
+
+impl ServiceCondition {
+    /// 评估启动条件，返回未满足时的原因
+    pub fn evaluate(&self, working_dir: &Path) -> Result<(), String> {
+        if let Some(file) = &self.file_exists {
+            let path = working_dir.join(file);
+            if !path.exists() {
+                return Err(format!("文件不存在: {:?}", path));
+            }
+        }
+
+        if let Some(registry) = &self.registry_value {
+            if !registry_value_exists(registry) {
+                return Err(format!(
+                    "注册表值不存在: {}\\{}\\{}",
+                    registry.hive, registry.key, registry.name
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+fn registry_value_exists(condition: &RegistryValueCondition) -> bool {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr::null_mut;
+    use winapi::shared::winerror::ERROR_SUCCESS;
+    use winapi::um::winreg::{
+        RegGetValueW, HKEY_CLASSES_ROOT, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, HKEY_USERS,
+        RRF_RT_ANY,
+    };
+
+    let hive = match condition.hive.to_ascii_uppercase().as_str() {
+        "HKLM" | "HKEY_LOCAL_MACHINE" => HKEY_LOCAL_MACHINE,
+        "HKCU" | "HKEY_CURRENT_USER" => HKEY_CURRENT_USER,
+        "HKCR" | "HKEY_CLASSES_ROOT" => HKEY_CLASSES_ROOT,
+        "HKU" | "HKEY_USERS" => HKEY_USERS,
+        other => {
+            warn!("不支持的注册表根键: {}", other);
+            return false;
+        }
+    };
+
+    let to_wide = |s: &str| {
+        OsStr::new(s)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect::<Vec<_>>()
+    };
+    let key = to_wide(&condition.key);
+    let name = to_wide(&condition.name);
+
+    let result = unsafe {
+        RegGetValueW(
+            hive,
+            key.as_ptr(),
+            name.as_ptr(),
+            RRF_RT_ANY,
+            null_mut(),
+            null_mut(),
+            null_mut(),
+        )
+    };
+    result == ERROR_SUCCESS as i32
+}
+
+#[cfg(not(windows))]
+fn registry_value_exists(_condition: &RegistryValueCondition) -> bool {
+    warn!("当前平台不支持注册表条件，视为不满足");
+    false
+}
@@ -0,0 +1,1533 @@
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, error, info, warn};
+
+use crate::cleanup::CleanupAction;
+use crate::condition::ServiceCondition;
+use crate::drain::DrainConfig;
+use crate::duration;
+use crate::env_file;
+use crate::error::ServiceError;
+use crate::health_export::HealthExport;
+use crate::job::ResourceLimits;
+use crate::jsonc;
+use crate::log_level::LogLevelConfig;
+use crate::logs;
+use crate::marker::ReadyFileConfig;
+use crate::network::NetworkWaitConfig;
+use crate::precondition::Preconditions;
+use crate::priority::ProcessPriority;
+use crate::process_match::{self, ProcessMatch};
+use crate::ready_log;
+use crate::signal::StopSignal;
+use crate::tcp_probe;
+use crate::update::UpdateConfig;
+use crate::window::WindowMode;
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ServiceConfig {
+    pub name: String,
+    pub executable: String,
+    pub working_dir: String,
+    #[serde(default)]
+    pub debug: bool, // 旧字段，等同于 window_mode: "visible"
+    #[serde(default)]
+    pub window_mode: Option<WindowMode>, // "hidden"（默认）、"visible" 或 "minimized"，优先于 debug
+    #[serde(default)]
+    pub args: Vec<String>, // 默认为空数组
+    #[serde(default)]
+    pub platform_args: HashMap<String, Vec<String>>, // 按平台追加的参数，键为 windows、linux、macos 或 unix
+    #[serde(default)]
+    pub health_check: Option<HealthCheckConfig>, // 可选字段
+    #[schemars(schema_with = "duration::opt_ms_schema")]
+    #[serde(default, deserialize_with = "duration::deserialize_opt_ms")]
+    pub kill_timeout_ms: Option<u64>, // 覆盖全局 shutdown_timeout_ms
+    #[serde(default)]
+    pub stop_signal: Option<StopSignal>, // 非 Windows 平台正常停止时发送的信号，默认 TERM
+    #[serde(default = "default_critical")]
+    pub critical: bool, // 关键服务失败时 --verify-only 以非零退出码退出，默认为 true
+    #[serde(default = "default_kill_existing")]
+    pub kill_existing: bool, // 启动前终止同名进程，默认为 true
+    #[serde(default = "default_spawn_retries")]
+    pub spawn_retries: u32, // 可执行文件被暂时占用导致创建进程失败时的重试次数，默认 3
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_startup_delay_ms",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub startup_delay_ms: u64, // 进程启动后等待多久再开始健康检查，默认 2000
+    #[serde(default)]
+    pub process_match: ProcessMatch, // 按进程名查找进程时的匹配方式，默认 exact
+    #[serde(default)]
+    pub cleanup_patterns: Vec<String>, // 停止服务时额外终止的辅助进程名通配符模式
+    #[serde(default)]
+    pub condition: Option<ServiceCondition>, // 可选的启动条件
+    #[serde(default)]
+    pub preconditions: Option<Preconditions>, // 启动前检查的磁盘与内存下限，覆盖顶层设置
+    #[serde(default)]
+    pub shell: bool, // 为 true 时 executable 作为命令行交给 cmd /C 或 sh -c 执行
+    #[serde(default)]
+    pub public_url: Option<String>, // 前端实际打开的地址，未设置时使用健康检查 url
+    #[serde(default)]
+    pub update_check: Option<UpdateConfig>, // 暂存目录中的新版本在 apply_updates 时替换当前可执行文件
+    #[serde(default)]
+    pub depends_on: Vec<String>, // 所依赖的服务名称，用于重启依赖链与启动前的就绪检查
+    #[serde(default)]
+    pub restart_dependents: bool, // 重启后按依赖顺序重启所有直接或间接依赖本服务的服务
+    #[serde(default)]
+    pub limits: Option<ResourceLimits>, // 进程树的内存与 CPU 限制，仅 Windows 生效
+    #[serde(default)]
+    pub pipe_stdin: bool, // 无窗口模式下把标准输入改为管道，可通过 write_service_stdin 写入
+    #[serde(default)]
+    pub env: HashMap<String, String>, // 额外设置的环境变量，覆盖 env_file 中的同名变量
+    #[serde(default)]
+    pub stderr_to_stdout: bool, // 捕获输出时把标准错误并入标准输出的管道，保留原始输出顺序
+    #[serde(default)]
+    pub kind: ServiceKind, // 默认为常驻服务
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_oneshot_timeout",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub oneshot_timeout_ms: u64, // 一次性任务等待退出的时间，超时视为失败
+    #[serde(default)]
+    pub drain: Option<DrainConfig>, // 重启或更新前先请求服务排空
+    #[serde(default)]
+    pub cleanup: Vec<CleanupAction>, // 每次启动前清理上次运行遗留的资源，相对路径以 working_dir 为基准
+    #[serde(default)]
+    pub log_level_control: Option<LogLevelConfig>, // 服务支持运行时调整日志级别时，set_log_level 的通知方式
+    #[serde(default)]
+    pub priority: ProcessPriority, // 进程调度优先级，默认 normal
+    #[serde(default)]
+    pub start_priority: i32, // 启动优先级，数值大的先启动，相同时按配置顺序；默认 0
+    #[serde(default)]
+    pub umask: Option<String>, // 服务进程的 umask（八进制，如 "002"），仅非 Windows 平台生效
+    #[serde(default)]
+    pub group: Option<String>, // 所属分组，可通过 start_group 等命令整组启停
+    #[serde(default)]
+    pub keep_on_failure: bool, // 健康检查失败后保留进程以便调试，不自动关闭应用
+    #[serde(default)]
+    pub lazy: bool, // 应用启动时不启动，首次通过 start_service 请求时再启动
+    #[serde(default)]
+    pub detached: bool, // xcontrol 退出时不停止该服务，进程独立运行；不捕获输出，也不放入 Job Object
+    #[schemars(schema_with = "duration::opt_ms_schema")]
+    #[serde(default, deserialize_with = "duration::deserialize_opt_ms")]
+    pub idle_timeout_ms: Option<u64>, // 超过该时间未收到 touch_service 时自动停止
+    #[serde(default)]
+    pub watch: Vec<String>, // 以 --watch 启动时监视的文件或目录，变化后自动重启服务
+    #[serde(default)]
+    pub auto_restart: bool, // 进程意外退出后自动重启
+    #[serde(default = "default_restart_burst_limit")]
+    pub restart_burst_limit: u32, // 窗口期内最多自动重启次数，超出后熔断
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_restart_burst_window",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub restart_burst_window_ms: u64, // 统计自动重启次数的时间窗口
+}
+
+/// start_service_with_overrides 使用的临时配置，只影响这一次启动，不写入配置文件
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct ServiceConfigOverrides {
+    #[serde(default)]
+    pub executable: Option<String>, // 替换可执行文件，相对路径仍以 working_dir 为基准
+    #[serde(default)]
+    pub extra_args: Vec<String>, // 追加在配置的 args 之后
+    #[serde(default)]
+    pub env: HashMap<String, String>, // 额外设置的环境变量，覆盖同名变量
+    #[serde(default)]
+    pub debug: Option<bool>, // 为 true 时显示控制台窗口，为 false 时隐藏，优先于 window_mode
+}
+
+impl ServiceConfigOverrides {
+    /// 返回合并临时配置后的服务配置
+    pub fn apply(&self, service: &ServiceConfig) -> ServiceConfig {
+        let mut service = service.clone();
+        if let Some(executable) = &self.executable {
+            service.executable = executable.clone();
+        }
+        service.args.extend(self.extra_args.iter().cloned());
+        service.env.extend(self.env.clone());
+        if let Some(debug) = self.debug {
+            service.window_mode = Some(if debug {
+                WindowMode::Visible
+            } else {
+                WindowMode::Hidden
+            });
+        }
+        service
+    }
+}
+
+/// 服务类型
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceKind {
+    /// 常驻服务，通过健康检查判断就绪，意外退出视为崩溃
+    #[default]
+    Service,
+    /// 一次性任务（如数据库迁移），以退出码 0 退出视为完成
+    Oneshot,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct HealthCheckConfig {
+    #[serde(default)]
+    pub enabled: bool, // 默认为 false
+    #[serde(default)]
+    pub check_type: HealthCheckType, // 默认为 http
+    #[serde(default)]
+    pub file: Option<ReadyFileConfig>, // check_type 为 file 时检查的就绪标记文件
+    #[serde(default)]
+    pub address: Option<String>, // check_type 为 tcp 时连接的地址，如 localhost:8080
+    #[serde(default)]
+    pub ready_pattern: Option<String>, // check_type 为 log 时匹配服务输出的正则表达式
+    #[serde(default)]
+    pub grpc_service: Option<String>, // check_type 为 grpc 时检查的服务名，未设置时检查整个服务器
+    #[serde(default)]
+    pub url: String, // 默认为空字符串
+    #[serde(default)]
+    pub endpoint: String, // 默认为空字符串
+    #[serde(default = "default_max_retries")]
+    pub max_retries: usize, // 最多检查次数，为 0 时按 1 次处理
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_retry_interval",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub retry_interval_ms: u64,
+    #[serde(default)]
+    pub auth_bearer: Option<String>, // 健康检查请求的 Bearer 令牌，支持 secret:// 引用
+    #[serde(default = "default_follow_redirects")]
+    pub follow_redirects: bool, // 为 false 时重定向响应视为未就绪
+    #[serde(default)]
+    pub accept_invalid_certs: bool, // 跳过 HTTPS 证书校验，默认 false
+    #[serde(default)]
+    pub ca_cert_path: Option<String>, // 额外信任的根证书（PEM 或 DER），用于内部 CA 签发的证书
+    #[serde(default)]
+    pub export: Option<HealthExport>, // 就绪时从 HTTP 响应中提取并发送给前端的字段
+    #[serde(default)]
+    pub progress_json_pointer: Option<String>, // 未就绪响应中启动进度（0-1）的 JSON 指针
+    #[serde(default)]
+    pub min_cert_days_remaining: Option<u32>, // HTTPS 证书剩余有效天数低于该值时检查失败
+    #[schemars(schema_with = "duration::opt_ms_schema")]
+    #[serde(default, deserialize_with = "duration::deserialize_opt_ms")]
+    pub connect_timeout_ms: Option<u64>, // 建立连接的超时，默认不限制
+    #[schemars(schema_with = "duration::opt_ms_schema")]
+    #[serde(default, deserialize_with = "duration::deserialize_opt_ms")]
+    pub request_timeout_ms: Option<u64>, // 单次请求（含连接与读取响应）的超时，默认不限制
+    #[serde(default)]
+    pub proxy: Option<String>, // 健康检查请求使用的代理地址，支持 ${ENV_VAR} 引用环境变量
+}
+
+impl HealthCheckConfig {
+    /// 实际检查次数：max_retries 为 0 时也至少检查一次，不会未经检查就判定失败
+    pub fn attempts(&self) -> usize {
+        self.max_retries.max(1)
+    }
+
+    /// 展开环境变量后的代理地址；未配置或展开后为空时返回 None，沿用默认的系统代理设置
+    pub fn proxy_url(&self) -> Option<String> {
+        self.proxy
+            .as_deref()
+            .map(expand_env_vars)
+            .map(|proxy| proxy.trim().to_string())
+            .filter(|proxy| !proxy.is_empty())
+    }
+}
+
+/// 健康检查方式
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCheckType {
+    /// 请求 url + endpoint，返回成功状态码视为就绪
+    #[default]
+    Http,
+    /// 轮询就绪标记文件，文件出现视为就绪
+    File,
+    /// 连接 address，解析出的任一地址连接成功视为就绪
+    Tcp,
+    /// 服务输出中出现匹配 ready_pattern 的行视为就绪
+    Log,
+    /// 按标准 gRPC 健康检查协议调用 url 上的 grpc.health.v1.Health/Check，返回 SERVING 视为就绪
+    Grpc,
+}
+
+// 为 HealthCheckConfig 实现 Default trait
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_type: HealthCheckType::default(),
+            file: None,
+            address: None,
+            ready_pattern: None,
+            grpc_service: None,
+            url: String::new(),
+            endpoint: String::new(),
+            max_retries: default_max_retries(),
+            retry_interval_ms: default_retry_interval(),
+            auth_bearer: None,
+            follow_redirects: default_follow_redirects(),
+            accept_invalid_certs: false,
+            ca_cert_path: None,
+            export: None,
+            progress_json_pointer: None,
+            min_cert_days_remaining: None,
+            connect_timeout_ms: None,
+            request_timeout_ms: None,
+            proxy: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ServicesConfig {
+    #[serde(default)]
+    pub version: Option<u32>, // 配置格式版本，未设置视为 1
+    pub services: Vec<ServiceConfig>,
+    #[serde(default)]
+    pub admin_server: Option<AdminServerConfig>, // 可选的只读管理服务器
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_shutdown_timeout",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub shutdown_timeout_ms: u64, // 正常退出的等待时间，超时后强制终止
+    #[schemars(schema_with = "duration::opt_ms_schema")]
+    #[serde(default, deserialize_with = "duration::deserialize_opt_ms")]
+    pub global_startup_timeout_ms: Option<u64>, // 整体启动时间预算，超时后中止启动
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_log_batch_interval",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub log_batch_interval_ms: u64, // 日志批量事件的发送间隔
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(default, deserialize_with = "duration::deserialize_ms")]
+    pub start_stagger_ms: u64, // 同一启动批次内相邻两个服务开始启动之间的间隔（另加最多一半的随机抖动），不等待前一个就绪，默认 0
+    #[serde(default)]
+    pub splash: Option<SplashConfig>, // 可选的启动窗口，启动完成后关闭并显示主窗口
+    #[serde(default)]
+    pub wait_for_network: Option<NetworkWaitConfig>, // 启动服务前等待网络可用
+    #[serde(default)]
+    pub include: Vec<String>, // 额外加载服务定义的文件，文件名支持 * 和 ? 通配符
+    #[serde(default)]
+    pub env_file: Option<String>, // 所有服务共用的 KEY=VALUE 环境变量文件
+    #[serde(default = "default_cleanup_window_classes")]
+    pub cleanup_window_classes_on_exit: bool, // 退出时注销 Chromium 窗口类（仅 Windows），默认 true
+    #[serde(default)]
+    pub cleanup_on_exit: Vec<CleanupAction>, // xcontrol 退出时执行的清理，相对路径以配置文件所在目录为基准
+    #[serde(default = "default_main_window_label")]
+    pub main_window_label: String, // tauri.conf.json 中主窗口的 label，默认 main
+    #[serde(default)]
+    pub persist_restart_stats: bool, // 重启统计写入文件，xcontrol 重新启动后保留，默认 false
+    #[serde(default)]
+    pub preconditions: Option<Preconditions>, // 所有服务启动前检查的磁盘与内存下限
+    #[serde(default)]
+    pub duplicate_instance_action: DuplicateInstanceAction, // 检测到重复启动时的处理方式，默认 focus
+}
+
+/// 检测到重复启动时已运行实例的处理方式
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateInstanceAction {
+    /// 显示并聚焦已有窗口
+    #[default]
+    Focus,
+    /// 不做任何处理，适用于自助终端等场景
+    Ignore,
+    /// 停止所有服务后以新启动的参数重新启动，由新实例替换当前实例
+    Replace,
+}
+
+/// include 引入的配置片段，只读取其中的服务列表
+#[derive(Deserialize, JsonSchema)]
+struct ServicesFragment {
+    #[serde(default)]
+    services: Vec<ServiceConfig>,
+}
+
+/// 当前版本能够理解的最高配置格式版本
+pub const SUPPORTED_CONFIG_VERSION: u32 = 1;
+
+/// 未配置 main_window_label 时使用的主窗口 label
+pub const DEFAULT_MAIN_WINDOW_LABEL: &str = "main";
+
+impl ServicesConfig {
+    /// xcontrol 退出时执行的清理：cleanup_window_classes_on_exit 对应的 Chromium 窗口类在前，其后为 cleanup_on_exit
+    pub fn exit_cleanup_actions(&self) -> Vec<CleanupAction> {
+        let window_class = self
+            .cleanup_window_classes_on_exit
+            .then(CleanupAction::chromium_window_class);
+        window_class
+            .into_iter()
+            .chain(self.cleanup_on_exit.iter().cloned())
+            .collect()
+    }
+
+    /// 配置格式版本高于当前版本支持的范围时返回警告信息
+    pub fn version_warning(&self) -> Option<String> {
+        self.version
+            .filter(|version| *version > SUPPORTED_CONFIG_VERSION)
+            .map(|version| {
+                format!(
+                    "配置文件版本 {} 高于当前 xcontrol 支持的版本 {}，部分配置可能被忽略",
+                    version, SUPPORTED_CONFIG_VERSION
+                )
+            })
+    }
+
+    /// 多个服务使用同一可执行文件时返回警告：按进程名停止其中一个服务会同时终止其他服务
+    pub fn shared_executable_warnings(&self) -> Vec<String> {
+        let mut by_name: IndexMap<String, Vec<&str>> = IndexMap::new();
+        for service in &self.services {
+            let file_name = Path::new(&service.executable)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            by_name.entry(file_name).or_default().push(&service.name);
+        }
+        by_name
+            .into_iter()
+            .filter(|(_, names)| names.len() > 1)
+            .map(|(file_name, names)| {
+                format!(
+                    "服务 {} 使用同一可执行文件 {}，按进程名停止其中一个服务时会同时终止其他服务",
+                    names.join("、"),
+                    file_name
+                )
+            })
+            .collect()
+    }
+
+    /// 配置可以加载但需要提示用户的所有问题
+    pub fn warnings(&self) -> Vec<String> {
+        self.version_warning()
+            .into_iter()
+            .chain(self.shared_executable_warnings())
+            .collect()
+    }
+}
+
+/// 启动窗口配置
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct SplashConfig {
+    #[serde(default = "default_splash_url")]
+    pub url: String, // 前端资源中的页面路径
+    #[serde(default = "default_splash_width")]
+    pub width: f64,
+    #[serde(default = "default_splash_height")]
+    pub height: f64,
+}
+
+/// 管理服务器配置，出于安全考虑默认关闭
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct AdminServerConfig {
+    #[serde(default)]
+    pub enabled: bool, // 默认为 false
+    #[serde(default = "default_admin_bind")]
+    pub bind: String,
+}
+
+fn default_max_retries() -> usize {
+    30
+}
+
+fn default_retry_interval() -> u64 {
+    1000
+}
+fn default_critical() -> bool {
+    true
+}
+fn default_cleanup_window_classes() -> bool {
+    true
+}
+fn default_main_window_label() -> String {
+    DEFAULT_MAIN_WINDOW_LABEL.to_string()
+}
+fn default_kill_existing() -> bool {
+    true
+}
+fn default_spawn_retries() -> u32 {
+    3
+}
+fn default_startup_delay_ms() -> u64 {
+    2000
+}
+fn default_shutdown_timeout() -> u64 {
+    5000
+}
+fn default_admin_bind() -> String {
+    "127.0.0.1:9870".to_string()
+}
+fn default_follow_redirects() -> bool {
+    true
+}
+fn default_log_batch_interval() -> u64 {
+    100
+}
+fn default_splash_url() -> String {
+    "splash.html".to_string()
+}
+fn default_splash_width() -> f64 {
+    480.0
+}
+fn default_splash_height() -> f64 {
+    320.0
+}
+fn default_oneshot_timeout() -> u64 {
+    300_000
+}
+fn default_restart_burst_limit() -> u32 {
+    5
+}
+fn default_restart_burst_window() -> u64 {
+    60_000
+}
+
+/// 把文本中的 `${NAME}` 替换为环境变量的值，未设置的变量替换为空；未闭合的 `${` 原样保留
+pub fn expand_env_vars(text: &str) -> String {
+    expand_vars_with(text, |name| std::env::var(name).ok())
+}
+
+fn expand_vars_with(text: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(&lookup(&rest[start + 2..start + 2 + len]).unwrap_or_default());
+        rest = &rest[start + 3 + len..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 将相对路径解析到 base 目录下，绝对路径保持不变
+pub fn resolve_relative_to(base: &Path, path: &str) -> String {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_string_lossy().to_string()
+    } else {
+        let joined = base.join(path);
+        // absolute 会去掉路径中的 `.` 片段，便于日志阅读
+        std::path::absolute(&joined)
+            .unwrap_or(joined)
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+/// 解码配置文件：去掉记事本保存时添加的 UTF-8 BOM，非 UTF-8 内容按 GBK 处理
+pub fn decode_config_file(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    logs::decode_console_output(bytes)
+}
+
+/// 解析配置文件内容，相对路径以配置文件所在目录 config_dir 为基准解析
+pub fn parse_services_config(
+    content: &str,
+    config_dir: &Path,
+) -> Result<ServicesConfig, serde_json::Error> {
+    // 允许在配置文件中使用 // 和 /* */ 注释
+    let mut config: ServicesConfig = serde_json::from_str(&jsonc::strip_comments(content))?;
+    resolve_service_paths(&mut config.services, config_dir);
+    for action in &mut config.cleanup_on_exit {
+        action.resolve_relative_to(config_dir);
+    }
+    Ok(config)
+}
+
+/// 相对路径以配置文件所在目录为基准，而不是进程的当前工作目录
+fn resolve_service_paths(services: &mut [ServiceConfig], config_dir: &Path) {
+    for service in services {
+        service.working_dir = resolve_relative_to(config_dir, &service.working_dir);
+        if let Some(update) = &mut service.update_check {
+            update.staging_dir = resolve_relative_to(config_dir, &update.staging_dir);
+        }
+        if let Some(ca_cert_path) = service
+            .health_check
+            .as_mut()
+            .and_then(|h| h.ca_cert_path.as_mut())
+        {
+            *ca_cert_path = resolve_relative_to(config_dir, ca_cert_path);
+        }
+    }
+}
+
+/// 展开 include 中的一项，按文件名排序；不含通配符的路径必须存在，含通配符时可以没有匹配
+pub fn include_paths(config_dir: &Path, pattern: &str) -> std::io::Result<Vec<PathBuf>> {
+    let path = Path::new(&resolve_relative_to(config_dir, pattern)).to_path_buf();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if !file_name.contains(['*', '?']) {
+        if !path.is_file() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("找不到 include 文件: {:?}", path),
+            ));
+        }
+        return Ok(vec![path]);
+    }
+
+    let dir = path.parent().unwrap_or(config_dir);
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_file() && process_match::glob_matches(&file_name, &name) {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// 加载 include 引入的服务并追加到服务列表末尾，片段中的相对路径以片段所在目录为基准；
+/// 服务名称重复时返回错误
+pub fn load_includes(
+    config: &mut ServicesConfig,
+    config_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for pattern in &config.include {
+        for path in include_paths(config_dir, pattern)? {
+            let content = decode_config_file(&std::fs::read(&path)?);
+            let mut fragment: ServicesFragment =
+                serde_json::from_str(&jsonc::strip_comments(&content))
+                    .map_err(|e| format!("解析 include 文件 {:?} 失败: {}", path, e))?;
+            let fragment_dir = path.parent().unwrap_or(config_dir);
+            resolve_service_paths(&mut fragment.services, fragment_dir);
+            info!("从 {:?} 加载了 {} 个服务", path, fragment.services.len());
+            config.services.extend(fragment.services);
+        }
+    }
+
+    let mut names = std::collections::HashSet::new();
+    for service in &config.services {
+        if !names.insert(service.name.as_str()) {
+            return Err(format!("服务名称重复: {}", service.name).into());
+        }
+    }
+    Ok(())
+}
+
+/// 把 env_file 中的变量合并到每个服务的 env 中，服务自己设置的同名变量优先
+pub fn load_env_file(
+    config: &mut ServicesConfig,
+    config_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(env_file) = &config.env_file else {
+        return Ok(());
+    };
+    let path = resolve_relative_to(config_dir, env_file);
+    let bytes =
+        std::fs::read(&path).map_err(|e| format!("读取 env_file {:?} 失败: {}", path, e))?;
+    let content = decode_config_file(&bytes);
+    let vars =
+        env_file::parse(&content).map_err(|e| format!("解析 env_file {:?} 失败: {}", path, e))?;
+    info!("从 {:?} 加载了 {} 个环境变量", path, vars.len());
+    for service in &mut config.services {
+        for (key, value) in &vars {
+            service
+                .env
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+    Ok(())
+}
+
+/// 加载服务配置文件
+pub fn load_services_config() -> Result<ServicesConfig, Box<dyn std::error::Error + Send + Sync>> {
+    let possible_paths = config_search_paths()?;
+
+    info!("正在查找配置文件...");
+
+    for path in &possible_paths {
+        debug!("尝试路径: {:?}", path);
+        if path.exists() {
+            info!("找到配置文件: {:?}", path);
+            let config_content = decode_config_file(&std::fs::read(path)?);
+            let config_dir = std::path::absolute(path)?
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+            let mut config = parse_services_config(&config_content, &config_dir)?;
+            load_includes(&mut config, &config_dir)?;
+            load_env_file(&mut config, &config_dir)?;
+            for warning in config.warnings() {
+                warn!("{}", warning);
+            }
+
+            info!("成功加载配置，包含 {} 个服务", config.services.len());
+            return Ok(config);
+        }
+    }
+
+    // 如果找不到配置文件，输出当前工作目录和可执行文件路径用于调试
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("unknown"));
+    let exe_path = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("unknown"));
+
+    let error_msg = format!(
+        "在所有可能的位置都找不到 services.dat 配置文件。\n当前工作目录: {:?}\n可执行文件路径: {:?}\n尝试的路径: {:#?}",
+        current_dir, exe_path, possible_paths
+    );
+
+    error!("{}", error_msg);
+    Err(error_msg.into())
+}
+
+/// services.dat 的 JSON Schema，由配置结构体生成，供编辑器补全与校验
+pub fn json_schema() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!(ServicesConfig)).unwrap_or_default()
+}
+
+/// 按查找顺序排列的配置文件候选路径，使用第一个存在的文件
+pub fn config_search_paths() -> std::io::Result<Vec<PathBuf>> {
+    // 尝试多个可能的配置文件位置
+    let mut possible_paths = Vec::new();
+    // 用户配置目录 - 无需管理员权限即可覆盖安装目录中的默认配置
+    if let Some(config_dir) = dirs::config_dir() {
+        possible_paths.push(config_dir.join("xcontrol").join("services.dat"));
+    }
+    possible_paths.extend([
+        // 开发环境 - 项目根目录
+        PathBuf::from("services.dat"),
+        PathBuf::from("./services.dat"),
+        PathBuf::from("../services.dat"),
+        // 生产环境 - 可执行文件目录及其父目录
+        std::env::current_exe()?
+            .parent()
+            .unwrap()
+            .join("services.dat"),
+        std::env::current_exe()?
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("services.dat"),
+        // Tauri 应用目录
+        std::env::current_exe()?
+            .parent()
+            .unwrap()
+            .join("resources")
+            .join("services.dat"),
+    ]);
+    Ok(possible_paths)
+}
+
+/// 当前平台的完整参数：args 之后依次追加 unix（非 Windows 平台）和当前平台的参数，
+/// 其他平台的键被忽略
+pub fn service_args(service: &ServiceConfig) -> Vec<String> {
+    let mut args = service.args.clone();
+    let mut platforms = Vec::new();
+    if cfg!(unix) {
+        platforms.push("unix");
+    }
+    platforms.push(std::env::consts::OS);
+
+    for platform in platforms {
+        if let Some(extra) = service.platform_args.get(platform) {
+            args.extend(extra.iter().cloned());
+        }
+    }
+    args
+}
+
+/// 服务可执行文件的完整路径：executable 为绝对路径时直接使用，否则位于 working_dir 下
+pub fn service_executable_path(service: &ServiceConfig) -> PathBuf {
+    let executable = Path::new(&service.executable);
+    if executable.is_absolute() {
+        executable.to_path_buf()
+    } else {
+        Path::new(&service.working_dir).join(executable)
+    }
+}
+
+/// 检查工作目录存在且是目录
+pub fn check_working_dir(path: &Path) -> Result<(), ServiceError> {
+    if !path.exists() {
+        return Err(ServiceError::WorkingDirMissing(path.to_path_buf()));
+    }
+    if !path.is_dir() {
+        return Err(ServiceError::WorkingDirNotDirectory(path.to_path_buf()));
+    }
+    Ok(())
+}
+
+/// 启动前校验单个服务配置：健康检查 URL、工作目录与可执行文件
+pub fn validate_service(service: &ServiceConfig) -> Result<(), ServiceError> {
+    let health_check = get_health_check_config(service);
+    if health_check.enabled
+        && health_check.check_type == HealthCheckType::File
+        && health_check.file.is_none()
+    {
+        return Err(ServiceError::MissingReadyFile);
+    }
+    if health_check.enabled && health_check.check_type == HealthCheckType::Tcp {
+        let address = health_check.address.unwrap_or_default();
+        if !tcp_probe::is_valid_address(&address) {
+            return Err(ServiceError::InvalidTcpAddress(address));
+        }
+    }
+    if health_check.enabled && health_check.check_type == HealthCheckType::Log {
+        ready_log::compile(health_check.ready_pattern.as_deref().unwrap_or_default())
+            .map_err(ServiceError::InvalidReadyPattern)?;
+        if !service_window_mode(service).captures_output() {
+            return Err(ServiceError::ReadyLogNotCaptured);
+        }
+    }
+    if health_check.enabled && health_check.check_type == HealthCheckType::Grpc {
+        let url = build_health_check_url(&health_check.url, "")
+            .map_err(|e| ServiceError::InvalidHealthUrl(e.to_string()))?;
+        // gRPC 健康检查只使用明文 HTTP/2 连接
+        if url.scheme() != "http" {
+            return Err(ServiceError::InvalidHealthUrl(format!(
+                "gRPC 健康检查只支持 http:// 地址: {}",
+                url
+            )));
+        }
+    }
+    if health_check.enabled
+        && health_check.check_type == HealthCheckType::Http
+        && !health_check.url.is_empty()
+    {
+        build_health_check_url(&health_check.url, &health_check.endpoint)
+            .map_err(|e| ServiceError::InvalidHealthUrl(e.to_string()))?;
+    }
+
+    if let Some(pattern) = service
+        .cleanup_patterns
+        .iter()
+        .find(|pattern| !process_match::is_specific_pattern(pattern))
+    {
+        return Err(ServiceError::CleanupPatternTooBroad(pattern.clone()));
+    }
+
+    if let Some(umask) = service.umask.as_ref().filter(|u| parse_umask(u).is_none()) {
+        return Err(ServiceError::InvalidUmask(umask.clone()));
+    }
+
+    if service.detached {
+        validate_detached(service)?;
+    }
+
+    check_working_dir(Path::new(&service.working_dir))?;
+
+    // shell 模式下 executable 是命令行，只要求非空
+    if service.shell {
+        if service.executable.trim().is_empty() {
+            return Err(ServiceError::EmptyShellCommand);
+        }
+        return Ok(());
+    }
+
+    let exe_path = service_executable_path(service);
+    if !exe_path.exists() {
+        return Err(ServiceError::ExecutableMissing {
+            executable: service.executable.clone(),
+            path: exe_path,
+        });
+    }
+
+    Ok(())
+}
+
+/// 校验服务能否独立运行：独立运行的服务不捕获输出、标准输入为空，
+/// 配置了 detached 的服务与以 --detach 启动时的所有服务都需要满足
+pub fn validate_detached(service: &ServiceConfig) -> Result<(), ServiceError> {
+    let health_check = get_health_check_config(service);
+    if health_check.enabled && health_check.check_type == HealthCheckType::Log {
+        return Err(ServiceError::DetachedReadyLog);
+    }
+    if service.pipe_stdin {
+        return Err(ServiceError::DetachedPipeStdin);
+    }
+    Ok(())
+}
+
+/// 校验所有服务配置，返回未通过校验的服务及原因
+pub fn validate_services_config(config: &ServicesConfig) -> IndexMap<String, ServiceError> {
+    config
+        .services
+        .iter()
+        .filter_map(|service| {
+            validate_service(service)
+                .err()
+                .map(|e| (service.name.clone(), e))
+        })
+        .collect()
+}
+
+/// 按依赖关系深度优先遍历，服务在其所有依赖之后加入 order
+fn visit_dependencies<'a>(
+    services: &'a [ServiceConfig],
+    name: &str,
+    visiting: &mut Vec<String>,
+    order: &mut Vec<&'a ServiceConfig>,
+) -> Result<(), String> {
+    if order.iter().any(|s| s.name == name) {
+        return Ok(());
+    }
+    if visiting.iter().any(|n| n == name) {
+        return Err(format!(
+            "存在循环依赖: {} -> {}",
+            visiting.join(" -> "),
+            name
+        ));
+    }
+    let service = services
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("未找到服务: {}", name))?;
+    visiting.push(name.to_string());
+    for dep in &service.depends_on {
+        visit_dependencies(services, dep, visiting, order)?;
+    }
+    visiting.pop();
+    order.push(service);
+    Ok(())
+}
+
+/// 启动服务 name 所需的服务列表，依赖（含间接依赖）排在被依赖者之后、name 排在最后；
+/// 依赖不存在或存在循环依赖时返回错误
+pub fn dependency_start_order<'a>(
+    services: &'a [ServiceConfig],
+    name: &str,
+) -> Result<Vec<&'a ServiceConfig>, String> {
+    let mut order = Vec::new();
+    visit_dependencies(services, name, &mut Vec::new(), &mut order)?;
+    Ok(order)
+}
+
+/// 服务所在的启动批次：没有依赖的为 0，否则比其依赖中最大的批次大 1；不存在或构成循环的依赖被忽略
+fn dependency_tier<'a>(
+    services: &'a [ServiceConfig],
+    service: &'a ServiceConfig,
+    visiting: &mut Vec<&'a str>,
+    tiers: &mut HashMap<&'a str, usize>,
+) -> usize {
+    if let Some(&tier) = tiers.get(service.name.as_str()) {
+        return tier;
+    }
+    visiting.push(&service.name);
+    let mut tier = 0;
+    for dep in &service.depends_on {
+        if visiting.contains(&dep.as_str()) {
+            continue;
+        }
+        if let Some(dep) = services.iter().find(|s| &s.name == dep) {
+            tier = tier.max(dependency_tier(services, dep, visiting, tiers) + 1);
+        }
+    }
+    visiting.pop();
+    tiers.insert(&service.name, tier);
+    tier
+}
+
+/// 启动全部服务时的分批：每批的服务并发启动，所依赖的服务（含间接依赖）总在更早的批次中；
+/// 同一批内按 start_priority 从高到低排列，相同时保持配置顺序。依赖不存在或循环依赖由配置校验报告错误
+pub fn launch_tiers(services: &[ServiceConfig]) -> Vec<Vec<&ServiceConfig>> {
+    let mut memo = HashMap::new();
+    let mut tiers: Vec<Vec<&ServiceConfig>> = Vec::new();
+    for service in services {
+        let tier = dependency_tier(services, service, &mut Vec::new(), &mut memo);
+        if tiers.len() <= tier {
+            tiers.resize_with(tier + 1, Vec::new);
+        }
+        tiers[tier].push(service);
+    }
+    for tier in &mut tiers {
+        tier.sort_by_key(|service| std::cmp::Reverse(service.start_priority));
+    }
+    tiers
+}
+
+/// 将 names 中的服务按依赖关系排序，被依赖的服务（含间接依赖）排在前面；
+/// 结果只包含 names 中的服务，依赖不存在或存在循环依赖时返回错误
+pub fn sort_by_dependencies<'a>(
+    services: &'a [ServiceConfig],
+    names: &[&str],
+) -> Result<Vec<&'a ServiceConfig>, String> {
+    let mut order = Vec::new();
+    for name in names {
+        visit_dependencies(services, name, &mut Vec::new(), &mut order)?;
+    }
+    order.retain(|s| names.contains(&s.name.as_str()));
+    Ok(order)
+}
+
+/// 解析八进制的 umask，如 "022" 或 "0002"；不是 0 到 0777 之间的八进制数时返回 None
+pub fn parse_umask(value: &str) -> Option<u32> {
+    if value.is_empty() || value.len() > 4 {
+        return None;
+    }
+    u32::from_str_radix(value, 8)
+        .ok()
+        .filter(|mask| *mask <= 0o777)
+}
+
+/// 服务的窗口模式：未设置 window_mode 时 debug: true 视为 visible
+pub fn service_window_mode(service: &ServiceConfig) -> WindowMode {
+    service.window_mode.unwrap_or(if service.debug {
+        WindowMode::Visible
+    } else {
+        WindowMode::Hidden
+    })
+}
+
+/// 获取服务的健康检查配置，如果没有配置则返回默认配置
+pub fn get_health_check_config(service: &ServiceConfig) -> HealthCheckConfig {
+    service.health_check.clone().unwrap_or_default()
+}
+
+/// 拼接健康检查 URL：基础地址与 endpoint 之间保证恰好一个斜杠，endpoint 为空时不追加
+pub fn build_health_check_url(
+    url: &str,
+    endpoint: &str,
+) -> Result<reqwest::Url, Box<dyn std::error::Error + Send + Sync>> {
+    let joined = if endpoint.is_empty() {
+        url.to_string()
+    } else {
+        format!(
+            "{}/{}",
+            url.trim_end_matches('/'),
+            endpoint.trim_start_matches('/')
+        )
+    };
+
+    reqwest::Url::parse(&joined)
+        .map_err(|e| format!("健康检查 URL 无效: {} ({})", joined, e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(config: serde_json::Value) -> ServicesConfig {
+        parse_services_config(&config.to_string(), &std::env::temp_dir()).unwrap()
+    }
+
+    fn service(name: &str) -> serde_json::Value {
+        json!({"name": name, "executable": "app", "working_dir": std::env::temp_dir()})
+    }
+
+    #[test]
+    fn json_schema_covers_nested_config_and_durations() {
+        let schema = json_schema();
+        assert_eq!(schema["required"], json!(["services"]));
+        for name in ["ServiceConfig", "HealthCheckConfig", "CleanupAction"] {
+            assert!(schema["definitions"][name].is_object(), "{}", name);
+        }
+        assert_eq!(
+            schema["properties"]["shutdown_timeout_ms"]["type"],
+            json!(["integer", "string"])
+        );
+        let health_check = &schema["definitions"]["HealthCheckConfig"]["properties"];
+        assert!(health_check["request_timeout_ms"]["type"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("null")));
+    }
+
+    #[test]
+    fn parse_applies_defaults() {
+        let config = parse(json!({"services": [service("api")]}));
+        assert_eq!(config.shutdown_timeout_ms, 5000);
+        assert_eq!(config.log_batch_interval_ms, 100);
+        assert_eq!(config.start_stagger_ms, 0);
+        assert!(config.admin_server.is_none());
+        assert!(config.splash.is_none());
+        assert!(config.cleanup_window_classes_on_exit);
+        assert_eq!(
+            config.exit_cleanup_actions(),
+            [CleanupAction::chromium_window_class()]
+        );
+        assert_eq!(config.main_window_label, "main");
+        assert_eq!(
+            config.duplicate_instance_action,
+            DuplicateInstanceAction::Focus
+        );
+        assert!(config.version_warning().is_none());
+
+        let service = &config.services[0];
+        assert!(service.critical);
+        assert!(service.kill_existing);
+        assert!(!service.detached);
+        assert_eq!(service.spawn_retries, 3);
+        assert_eq!(service.startup_delay_ms, 2000);
+        assert!(!service.shell);
+        assert_eq!(service.kind, ServiceKind::Service);
+        assert_eq!(service.oneshot_timeout_ms, 300_000);
+        assert!(!service.auto_restart);
+        assert!(service.idle_timeout_ms.is_none());
+        assert_eq!(service.restart_burst_limit, 5);
+        assert_eq!(service.restart_burst_window_ms, 60_000);
+        assert_eq!(service.process_match, ProcessMatch::Exact);
+        assert_eq!(service_window_mode(service), WindowMode::Hidden);
+
+        let health_check = get_health_check_config(service);
+        assert!(!health_check.enabled);
+        assert_eq!(health_check.check_type, HealthCheckType::Http);
+        assert_eq!(health_check.max_retries, 30);
+        assert_eq!(health_check.retry_interval_ms, 1000);
+        assert!(health_check.follow_redirects);
+        assert!(!health_check.accept_invalid_certs);
+    }
+
+    #[test]
+    fn duration_fields_accept_humanized_strings() {
+        let mut api = service("api");
+        api["oneshot_timeout_ms"] = json!("5m");
+        api["kill_timeout_ms"] = json!("1500ms");
+        let config = parse(json!({"services": [api], "shutdown_timeout_ms": "10s"}));
+        assert_eq!(config.shutdown_timeout_ms, 10_000);
+        assert_eq!(config.services[0].oneshot_timeout_ms, 300_000);
+        assert_eq!(config.services[0].kill_timeout_ms, Some(1500));
+        let content = json!({"services": [], "start_stagger_ms": "later"}).to_string();
+        assert!(parse_services_config(&content, &std::env::temp_dir()).is_err());
+    }
+
+    #[test]
+    fn env_vars_are_expanded_in_proxy_urls() {
+        let lookup = |name: &str| (name == "HTTP_PROXY").then(|| "http://proxy:3128".to_string());
+        assert_eq!(
+            expand_vars_with("${HTTP_PROXY}", lookup),
+            "http://proxy:3128"
+        );
+        assert_eq!(
+            expand_vars_with("http://${USER}@proxy:${PORT}", lookup),
+            "http://@proxy:"
+        );
+        assert_eq!(
+            expand_vars_with("http://proxy:${8080", lookup),
+            "http://proxy:${8080"
+        );
+
+        let mut health_check = HealthCheckConfig::default();
+        assert_eq!(health_check.proxy_url(), None);
+        health_check.proxy = Some("${XCONTROL_UNSET_PROXY_VARIABLE}".to_string());
+        assert_eq!(health_check.proxy_url(), None);
+        health_check.proxy = Some(" http://proxy:3128 ".to_string());
+        assert_eq!(
+            health_check.proxy_url().as_deref(),
+            Some("http://proxy:3128")
+        );
+    }
+
+    #[test]
+    fn overrides_are_merged_onto_a_copy_of_the_service() {
+        let mut api = service("api");
+        api["args"] = json!(["--port", "8080"]);
+        api["env"] = json!({"LOG_LEVEL": "info", "DB_HOST": "db"});
+        api["window_mode"] = json!("minimized");
+        let config = parse(json!({"services": [api]}));
+        let overrides = ServiceConfigOverrides {
+            executable: Some("app-debug".to_string()),
+            extra_args: vec!["--verbose".to_string()],
+            env: HashMap::from([("LOG_LEVEL".to_string(), "debug".to_string())]),
+            debug: Some(true),
+        };
+
+        let service = overrides.apply(&config.services[0]);
+        assert_eq!(service.executable, "app-debug");
+        assert_eq!(service.args, ["--port", "8080", "--verbose"]);
+        assert_eq!(service.env["LOG_LEVEL"], "debug");
+        assert_eq!(service.env["DB_HOST"], "db");
+        assert_eq!(service_window_mode(&service), WindowMode::Visible);
+        assert_eq!(config.services[0].executable, "app");
+
+        let unchanged = ServiceConfigOverrides::default().apply(&config.services[0]);
+        assert_eq!(service_window_mode(&unchanged), WindowMode::Minimized);
+    }
+
+    #[test]
+    fn parse_accepts_comments() {
+        let content = r#"{
+            // 行注释
+            "services": [], /* 块注释 */
+            "shutdown_timeout_ms": 1000
+        }"#;
+        let config = parse_services_config(content, &std::env::temp_dir()).unwrap();
+        assert_eq!(config.shutdown_timeout_ms, 1000);
+    }
+
+    #[test]
+    fn newer_config_version_warns() {
+        let config = parse(json!({"version": SUPPORTED_CONFIG_VERSION + 1, "services": []}));
+        assert!(config.version_warning().is_some());
+    }
+
+    #[test]
+    fn services_sharing_an_executable_are_warned() {
+        let mut worker = service("worker");
+        worker["executable"] = json!("bin/Worker.exe");
+        let mut reports = service("reports");
+        reports["executable"] = json!("worker.exe");
+        let config = parse(json!({"services": [worker, service("api"), reports]}));
+        let warnings = config.shared_executable_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("worker、reports"));
+        assert!(parse(json!({"services": [service("api")]}))
+            .warnings()
+            .is_empty());
+    }
+
+    #[test]
+    fn relative_paths_resolve_against_config_dir() {
+        let config_dir = std::env::temp_dir();
+        let absolute_dir = config_dir.join("absolute");
+        let content = json!({"services": [
+            {"name": "api", "executable": "api", "working_dir": "./bin/api",
+             "health_check": {"ca_cert_path": "certs/ca.pem"}},
+            {"name": "web", "executable": "web", "working_dir": absolute_dir},
+        ]});
+        let config = parse_services_config(&content.to_string(), &config_dir).unwrap();
+
+        let api = &config.services[0];
+        assert_eq!(
+            Path::new(&api.working_dir),
+            config_dir.join("bin").join("api")
+        );
+        let ca_cert_path = get_health_check_config(api).ca_cert_path.unwrap();
+        assert_eq!(
+            Path::new(&ca_cert_path),
+            config_dir.join("certs").join("ca.pem")
+        );
+        assert_eq!(Path::new(&config.services[1].working_dir), absolute_dir);
+    }
+
+    #[test]
+    fn absolute_executable_ignores_working_dir() {
+        let config_dir = std::env::temp_dir();
+        let absolute_exe = config_dir.join("Program Files").join("My App").join("app");
+        let content = json!({"services": [
+            {"name": "api", "executable": absolute_exe, "working_dir": "./bin/api"},
+            {"name": "web", "executable": "my web/web server", "working_dir": "./web root"},
+        ]});
+        let config = parse_services_config(&content.to_string(), &config_dir).unwrap();
+
+        assert_eq!(service_executable_path(&config.services[0]), absolute_exe);
+        assert_eq!(
+            service_executable_path(&config.services[1]),
+            config_dir
+                .join("web root")
+                .join("my web")
+                .join("web server")
+        );
+    }
+
+    #[test]
+    fn parse_accepts_bom_prefixed_file() {
+        let mut bytes = b"\xEF\xBB\xBF".to_vec();
+        bytes.extend(
+            json!({"services": [service("api")]})
+                .to_string()
+                .into_bytes(),
+        );
+        let content = decode_config_file(&bytes);
+        let config = parse_services_config(&content, &std::env::temp_dir()).unwrap();
+        assert_eq!(config.services[0].name, "api");
+    }
+
+    #[test]
+    fn gbk_config_is_decoded() {
+        // "服务" 的 GBK 编码
+        let bytes = b"{\"services\": [], \"note\": \"\xB7\xFE\xCE\xF1\"}";
+        assert!(decode_config_file(bytes).contains("服务"));
+    }
+
+    #[test]
+    fn includes_load_matching_fragments_in_name_order() {
+        let config_dir =
+            std::env::temp_dir().join(format!("xcontrol-include-{}", std::process::id()));
+        let teams_dir = config_dir.join("teams");
+        std::fs::create_dir_all(&teams_dir).unwrap();
+        std::fs::write(
+            teams_dir.join("b.dat"),
+            json!({"services": [{"name": "web", "executable": "web", "working_dir": "./web"}]})
+                .to_string(),
+        )
+        .unwrap();
+        std::fs::write(
+            teams_dir.join("a.dat"),
+            "// 团队 A\n{\"services\": [{\"name\": \"worker\", \"executable\": \"w\", \"working_dir\": \".\"}]}",
+        )
+        .unwrap();
+        std::fs::write(teams_dir.join("notes.txt"), "").unwrap();
+
+        let mut config = parse(json!({"services": [service("api")], "include": ["teams/*.dat"]}));
+        load_includes(&mut config, &config_dir).unwrap();
+        let names: Vec<_> = config.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["api", "worker", "web"]);
+        assert_eq!(
+            Path::new(&config.services[2].working_dir),
+            teams_dir.join("web")
+        );
+
+        let mut duplicate =
+            parse(json!({"services": [service("web")], "include": ["teams/b.dat"]}));
+        let error = load_includes(&mut duplicate, &config_dir).unwrap_err();
+        assert!(error.to_string().contains("web"));
+
+        let mut missing = parse(json!({"services": [], "include": ["teams/c.dat"]}));
+        assert!(load_includes(&mut missing, &config_dir).is_err());
+        std::fs::remove_dir_all(&config_dir).unwrap();
+    }
+
+    #[test]
+    fn env_file_applies_to_all_services_unless_overridden() {
+        let config_dir = std::env::temp_dir().join(format!("xcontrol-env-{}", std::process::id()));
+        std::fs::create_dir_all(&config_dir).unwrap();
+        // 记事本保存的文件带 UTF-8 BOM
+        std::fs::write(
+            config_dir.join("shared.env"),
+            "\u{FEFF}DB_HOST=db\nDB_PORT=5432\n",
+        )
+        .unwrap();
+
+        let mut api = service("api");
+        api["env"] = json!({"DB_HOST": "localhost"});
+        let mut config =
+            parse(json!({"services": [api, service("web")], "env_file": "shared.env"}));
+        load_env_file(&mut config, &config_dir).unwrap();
+        assert_eq!(config.services[0].env["DB_HOST"], "localhost");
+        assert_eq!(config.services[0].env["DB_PORT"], "5432");
+        assert_eq!(config.services[1].env["DB_HOST"], "db");
+
+        let mut missing = parse(json!({"services": [], "env_file": "missing.env"}));
+        assert!(load_env_file(&mut missing, &config_dir).is_err());
+        std::fs::remove_dir_all(&config_dir).unwrap();
+    }
+
+    #[test]
+    fn window_mode_overrides_legacy_debug_flag() {
+        let mut visible = service("api");
+        visible["debug"] = json!(true);
+        let mut minimized = visible.clone();
+        minimized["window_mode"] = json!("minimized");
+
+        let config = parse(json!({"services": [visible, minimized]}));
+        assert_eq!(
+            service_window_mode(&config.services[0]),
+            WindowMode::Visible
+        );
+        assert_eq!(
+            service_window_mode(&config.services[1]),
+            WindowMode::Minimized
+        );
+    }
+
+    #[test]
+    fn platform_args_follow_common_args() {
+        let mut api = service("api");
+        api["args"] = json!(["--port", "8080"]);
+        api["platform_args"] = json!({
+            std::env::consts::OS: ["--native"],
+            "unix": ["--unix"],
+            "unknown": ["--ignored"],
+        });
+        let config = parse(json!({"services": [api]}));
+
+        let expected: &[&str] = if cfg!(unix) {
+            &["--port", "8080", "--unix", "--native"]
+        } else {
+            &["--port", "8080", "--native"]
+        };
+        assert_eq!(service_args(&config.services[0]), expected);
+    }
+
+    #[test]
+    fn health_check_url_joins_with_single_slash() {
+        let join = |url, endpoint| build_health_check_url(url, endpoint).unwrap().to_string();
+        assert_eq!(
+            join("http://127.0.0.1:8080", "health"),
+            "http://127.0.0.1:8080/health"
+        );
+        assert_eq!(
+            join("http://127.0.0.1:8080/", "/health"),
+            "http://127.0.0.1:8080/health"
+        );
+        assert_eq!(
+            join("http://127.0.0.1:8080/api/", "v1/health"),
+            "http://127.0.0.1:8080/api/v1/health"
+        );
+        assert_eq!(join("http://127.0.0.1:8080", ""), "http://127.0.0.1:8080/");
+    }
+
+    #[test]
+    fn invalid_health_check_url_is_rejected() {
+        assert!(build_health_check_url("127.0.0.1:8080", "health").is_err());
+        assert!(build_health_check_url("", "health").is_err());
+    }
+
+    #[test]
+    fn zero_max_retries_still_checks_once() {
+        let attempts = |max_retries| {
+            HealthCheckConfig {
+                max_retries,
+                ..Default::default()
+            }
+            .attempts()
+        };
+        assert_eq!(attempts(0), 1);
+        assert_eq!(attempts(1), 1);
+        assert_eq!(attempts(2), 2);
+    }
+
+    #[test]
+    fn log_check_requires_pattern_and_captured_output() {
+        let mut missing = service("api");
+        missing["health_check"] = json!({"enabled": true, "check_type": "log"});
+        let mut visible = service("web");
+        visible["health_check"] =
+            json!({"enabled": true, "check_type": "log", "ready_pattern": "listening"});
+        visible["window_mode"] = json!("visible");
+
+        let config = parse(json!({"services": [missing, visible]}));
+        assert!(matches!(
+            validate_service(&config.services[0]),
+            Err(ServiceError::InvalidReadyPattern(_))
+        ));
+        assert!(matches!(
+            validate_service(&config.services[1]),
+            Err(ServiceError::ReadyLogNotCaptured)
+        ));
+    }
+
+    #[test]
+    fn grpc_check_requires_plaintext_url() {
+        let grpc = |url: &str| {
+            let mut service = service("orders");
+            service["health_check"] = json!({"enabled": true, "check_type": "grpc", "url": url});
+            parse(json!({"services": [service]})).services.remove(0)
+        };
+        // 地址通过校验，之后因可执行文件不存在失败
+        assert!(matches!(
+            validate_service(&grpc("http://127.0.0.1:50051")),
+            Err(ServiceError::ExecutableMissing { .. })
+        ));
+        for url in ["https://127.0.0.1:50051", ""] {
+            assert!(matches!(
+                validate_service(&grpc(url)),
+                Err(ServiceError::InvalidHealthUrl(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn detached_services_cannot_rely_on_output_or_stdin() {
+        let mut log = service("api");
+        log["health_check"] =
+            json!({"enabled": true, "check_type": "log", "ready_pattern": "listening"});
+        let mut stdin = service("repl");
+        stdin["pipe_stdin"] = json!(true);
+        let config = parse(json!({"services": [log.clone(), stdin.clone()]}));
+        assert!(validate_detached(&config.services[0]).is_err());
+        assert!(matches!(
+            validate_detached(&config.services[1]),
+            Err(ServiceError::DetachedPipeStdin)
+        ));
+        assert!(
+            validate_detached(&parse(json!({"services": [service("web")]})).services[0]).is_ok()
+        );
+
+        log["detached"] = json!(true);
+        let config = parse(json!({"services": [log]}));
+        assert!(matches!(
+            validate_service(&config.services[0]),
+            Err(ServiceError::DetachedReadyLog)
+        ));
+    }
+
+    #[test]
+    fn dependency_start_order_puts_dependencies_first() {
+        let mut web = service("web");
+        web["depends_on"] = json!(["api", "cache"]);
+        let mut api = service("api");
+        api["depends_on"] = json!(["db"]);
+        let mut cache = service("cache");
+        cache["depends_on"] = json!(["db"]);
+        let config = parse(json!({"services": [web, api, cache, service("db")]}));
+
+        let order = dependency_start_order(&config.services, "web").unwrap();
+        let names: Vec<_> = order.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["db", "api", "cache", "web"]);
+        assert!(dependency_start_order(&config.services, "missing").is_err());
+
+        let mut a = service("a");
+        a["depends_on"] = json!(["b"]);
+        let mut b = service("b");
+        b["depends_on"] = json!(["a"]);
+        let config = parse(json!({"services": [a, b]}));
+        assert!(dependency_start_order(&config.services, "a").is_err());
+    }
+
+    #[test]
+    fn launch_tiers_group_by_dependencies_and_sort_by_start_priority() {
+        let mut web = service("web");
+        web["start_priority"] = json!(10);
+        web["depends_on"] = json!(["api"]);
+        let mut cache = service("cache");
+        cache["start_priority"] = json!(50);
+        let mut db = service("db");
+        db["start_priority"] = json!(100);
+        let mut broken = service("broken");
+        broken["depends_on"] = json!(["missing"]);
+        let mut gateway = service("gateway");
+        gateway["depends_on"] = json!(["web", "db"]);
+        let config = parse(json!({
+            "services": [gateway, service("api"), web, broken, cache, db, service("worker")]
+        }));
+
+        let tiers = launch_tiers(&config.services);
+        let names: Vec<Vec<_>> = tiers
+            .iter()
+            .map(|tier| tier.iter().map(|s| s.name.as_str()).collect())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                vec!["db", "cache", "api", "broken", "worker"],
+                vec!["web"],
+                vec!["gateway"]
+            ]
+        );
+    }
+
+    #[test]
+    fn umask_must_be_octal_permission_bits() {
+        assert_eq!(parse_umask("022"), Some(0o022));
+        assert_eq!(parse_umask("0002"), Some(0o002));
+        assert_eq!(parse_umask("777"), Some(0o777));
+        assert_eq!(parse_umask(""), None);
+        assert_eq!(parse_umask("0o22"), None);
+        assert_eq!(parse_umask("089"), None);
+        assert_eq!(parse_umask("1000"), None);
+        assert_eq!(parse_umask("-22"), None);
+    }
+
+    #[test]
+    fn sort_by_dependencies_keeps_only_requested_services() {
+        let mut web = service("web");
+        web["depends_on"] = json!(["api"]);
+        let mut api = service("api");
+        api["depends_on"] = json!(["db"]);
+        let config = parse(json!({"services": [web, api, service("db"), service("cache")]}));
+
+        let order = sort_by_dependencies(&config.services, &["web", "cache", "db"]).unwrap();
+        let names: Vec<_> = order.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["db", "web", "cache"]);
+        assert!(sort_by_dependencies(&config.services, &["missing"]).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use tracing::info;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use xcontrol_lib::config::load_services_config;
+use xcontrol_lib::logs::{decode_console_output, LogStore};
+use xcontrol_lib::unix_timestamp_ms;
+
+use crate::{collect_services_status, get_processes_by_name, ProcessManager, ServiceInfo};
+
+/// 诊断包中每个服务保留的最近日志行数
+const DIAGNOSTIC_LOG_LINES: usize = 200;
+
+/// 替换明文机密时使用的占位符
+const REDACTED: &str = "<redacted>";
+
+/// 收集配置、启动报告、服务日志、系统信息和进程列表，打包为临时目录下的 zip 文件
+pub fn export(process_manager: &ProcessManager, log_store: &LogStore) -> Result<PathBuf, String> {
+    let path =
+        std::env::temp_dir().join(format!("xcontrol-diagnostics-{}.zip", unix_timestamp_ms()));
+    let file = File::create(&path).map_err(|e| format!("创建诊断包失败: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut add_file = |name: &str, content: String| -> Result<(), String> {
+        zip.start_file(name, options)
+            .and_then(|_| Ok(zip.write_all(content.as_bytes())?))
+            .map_err(|e| format!("写入诊断包 {} 失败: {}", name, e))
+    };
+
+    let config = load_services_config();
+    match &config {
+        Ok(config) => {
+            let mut value = serde_json::to_value(config).map_err(|e| e.to_string())?;
+            redact_secrets(&mut value);
+            let content = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+            add_file("config.json", content)?;
+        }
+        Err(e) => add_file("config_error.txt", e.to_string())?,
+    }
+
+    let state =
+        serde_json::to_string_pretty(&dump_state(process_manager)).map_err(|e| e.to_string())?;
+    add_file("state.json", state)?;
+
+    let statuses = collect_services_status(process_manager);
+    let report = serde_json::to_string_pretty(&statuses).map_err(|e| e.to_string())?;
+    add_file("startup_report.json", report)?;
+
+    for status in &statuses {
+        let lines = log_store.recent(&status.service_name).unwrap_or_default();
+        let start = lines.len().saturating_sub(DIAGNOSTIC_LOG_LINES);
+        let content: String = lines[start..]
+            .iter()
+            .map(|line| format!("{} [{}] {}\n", line.timestamp_ms, line.stream, line.line))
+            .collect();
+        add_file(
+            &format!("logs/{}.log", status.service_name.replace(['/', '\\'], "_")),
+            content,
+        )?;
+    }
+
+    add_file("system.txt", system_info())?;
+
+    let mut processes = String::new();
+    if let Ok(config) = &config {
+        for service in &config.services {
+            let line = match get_processes_by_name(&service.executable, service.process_match) {
+                Ok(pids) => format!("{} ({}): {:?}\n", service.name, service.executable, pids),
+                Err(e) => format!(
+                    "{} ({}): 查询失败: {}\n",
+                    service.name, service.executable, e
+                ),
+            };
+            processes.push_str(&line);
+        }
+    }
+    add_file("processes.txt", processes)?;
+
+    zip.finish().map_err(|e| format!("写入诊断包失败: {}", e))?;
+    info!("诊断包已导出: {:?}", path);
+    Ok(path)
+}
+
+/// dump_state 中单个服务的条目
+#[derive(Serialize)]
+struct ServiceSnapshot<'a> {
+    service_name: &'a str,
+    #[serde(flatten)]
+    info: &'a ServiceInfo,
+    restarts_total: u64,
+    uptime_ms: Option<u64>,
+    config: Value, // 当前配置文件中该服务的配置，已不在配置中时为 null
+}
+
+/// 内部服务登记表的完整快照：按登记顺序列出每个服务的状态、计数器与（隐藏机密后的）配置
+pub fn dump_state(process_manager: &ProcessManager) -> Value {
+    let (configs, config_error) = match load_services_config() {
+        Ok(config) => {
+            let mut value = serde_json::to_value(&config.services).unwrap_or_default();
+            redact_secrets(&mut value);
+            (value.as_array().cloned().unwrap_or_default(), None)
+        }
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+
+    let now = unix_timestamp_ms();
+    let manager = process_manager.lock().unwrap();
+    let services: Vec<_> = manager
+        .iter()
+        .map(|(name, info)| ServiceSnapshot {
+            service_name: name,
+            info,
+            restarts_total: info.restart_stats.count,
+            uptime_ms: info
+                .started_at_ms
+                .filter(|_| info.state.has_process())
+                .map(|started| now.saturating_sub(started)),
+            config: configs
+                .iter()
+                .find(|c| c["name"] == name.as_str())
+                .cloned()
+                .unwrap_or(Value::Null),
+        })
+        .collect();
+
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "timestamp_ms": now,
+        "config_error": config_error,
+        "services": services,
+    })
+}
+
+/// 隐藏配置中的明文机密；secret:// 引用本身不是机密，保留原样
+fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let is_plain_secret = key == "auth_bearer"
+                    && field.as_str().is_some_and(|s| !s.starts_with("secret://"));
+                if is_plain_secret {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+/// xcontrol 版本与操作系统信息
+fn system_info() -> String {
+    let mut info = format!(
+        "xcontrol {}\nos: {}\narch: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "ver"]);
+        cmd
+    } else {
+        let mut cmd = Command::new("uname");
+        cmd.arg("-a");
+        cmd
+    };
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(xcontrol_lib::window::CREATE_NO_WINDOW);
+    }
+    if let Ok(output) = cmd.output() {
+        info.push_str("version: ");
+        info.push_str(decode_console_output(&output.stdout).trim());
+        info.push('\n');
+    }
+
+    info
+}
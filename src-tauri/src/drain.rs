@@ -0,0 +1,41 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::duration;
+
+/// 停止前的排空配置：先通知服务不再接受新请求，等待进行中的请求完成后再停止
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct DrainConfig {
+    pub drain_url: String, // 收到 POST 请求后服务进入排空模式
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_drain_wait",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub drain_wait_ms: u64, // 进入排空模式后等待多久再停止
+}
+
+fn default_drain_wait() -> u64 {
+    10_000
+}
+
+/// 排空接口的请求超时
+const DRAIN_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 请求服务进入排空模式，接口返回成功状态码视为成功
+pub async fn request(drain: &DrainConfig) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(DRAIN_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .post(&drain.drain_url)
+        .send()
+        .await
+        .map_err(|e| format!("请求排空接口失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("排空接口返回状态码: {}", response.status()));
+    }
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, NumberValidation, Schema, SchemaObject};
+use serde::de::{self, Deserializer, Visitor};
+use serde::Deserialize;
+use std::fmt;
+
+/// 解析时长字符串，返回毫秒数：不带单位的整数按毫秒处理，其余按 humantime 的写法解析，
+/// 如 `500ms`、`30s`、`2m`、`1h 30min`、`1d`
+pub fn parse_ms(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    if let Ok(ms) = text.parse::<u64>() {
+        return Ok(ms);
+    }
+    let duration = humantime::parse_duration(text).map_err(|e| {
+        format!(
+            "无效的时长: {:?}（{}），应为毫秒数或如 \"500ms\"、\"30s\"、\"2m\" 的字符串",
+            text, e
+        )
+    })?;
+    u64::try_from(duration.as_millis()).map_err(|_| format!("时长过大: {:?}", text))
+}
+
+struct MsVisitor;
+
+impl Visitor<'_> for MsVisitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("毫秒数或时长字符串（如 \"30s\"）")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<u64, E> {
+        Ok(value)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<u64, E> {
+        u64::try_from(value).map_err(|_| E::custom(format!("时长不能为负数: {}", value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+        parse_ms(value).map_err(E::custom)
+    }
+}
+
+/// 反序列化以毫秒为单位的时长字段，同时接受整数毫秒与时长字符串
+pub fn deserialize_ms<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    deserializer.deserialize_any(MsVisitor)
+}
+
+/// 可选时长字段的 deserialize_with，null 视为未设置
+pub fn deserialize_opt_ms<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    struct Ms(#[serde(deserialize_with = "deserialize_ms")] u64);
+
+    Ok(Option::<Ms>::deserialize(deserializer)?.map(|Ms(ms)| ms))
+}
+
+fn duration_schema(null: bool) -> Schema {
+    let mut types = vec![InstanceType::Integer, InstanceType::String];
+    if null {
+        types.push(InstanceType::Null);
+    }
+    SchemaObject {
+        instance_type: Some(types.into()),
+        number: Some(Box::new(NumberValidation {
+            minimum: Some(0.0),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// 时长字段的 schema_with：整数毫秒或时长字符串
+pub fn ms_schema(_: &mut SchemaGenerator) -> Schema {
+    duration_schema(false)
+}
+
+/// 可选时长字段的 schema_with，另外允许 null
+pub fn opt_ms_schema(_: &mut SchemaGenerator) -> Schema {
+    duration_schema(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units_and_combinations() {
+        assert_eq!(parse_ms("500ms"), Ok(500));
+        assert_eq!(parse_ms("30s"), Ok(30_000));
+        assert_eq!(parse_ms("2m"), Ok(120_000));
+        assert_eq!(parse_ms("1h 30min"), Ok(5_400_000));
+        assert_eq!(parse_ms("1m30s"), Ok(90_000));
+        assert_eq!(parse_ms("1d"), Ok(86_400_000));
+        assert_eq!(parse_ms(" 60000 "), Ok(60_000));
+        assert_eq!(parse_ms("1.5s"), Ok(1500));
+    }
+
+    #[test]
+    fn rejects_malformed_durations() {
+        for text in ["", "s", "10x", "-5s", "99999999999999999999d"] {
+            assert!(parse_ms(text).is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn deserializes_numbers_strings_and_null() {
+        #[derive(Deserialize)]
+        struct Config {
+            #[serde(deserialize_with = "deserialize_ms")]
+            interval_ms: u64,
+            #[serde(default, deserialize_with = "deserialize_opt_ms")]
+            timeout_ms: Option<u64>,
+        }
+
+        let config: Config = serde_json::from_str(r#"{"interval_ms": 250}"#).unwrap();
+        assert_eq!((config.interval_ms, config.timeout_ms), (250, None));
+        let config: Config =
+            serde_json::from_str(r#"{"interval_ms": "2s", "timeout_ms": "1m"}"#).unwrap();
+        assert_eq!(
+            (config.interval_ms, config.timeout_ms),
+            (2000, Some(60_000))
+        );
+        let config: Config =
+            serde_json::from_str(r#"{"interval_ms": 1, "timeout_ms": null}"#).unwrap();
+        assert_eq!(config.timeout_ms, None);
+        assert!(serde_json::from_str::<Config>(r#"{"interval_ms": -1}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"interval_ms": "soon"}"#).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+/// 解析 .env 风格的文件，每行一个 `KEY=VALUE`，按出现顺序返回，重复的键以最后一次为准
+///
+/// 忽略空行与 `#` 开头的注释行，允许行首的 `export `；值可用双引号（支持 `\n`、`\"`、`\\` 转义）
+/// 或单引号（原样保留）包裹，未加引号的值去掉首尾空白及空白之后的 `#` 注释
+pub fn parse(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("第 {} 行缺少 =", index + 1))?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("第 {} 行的变量名无效: {:?}", index + 1, key));
+        }
+        let value =
+            parse_value(value.trim_start()).map_err(|e| format!("第 {} 行{}", index + 1, e))?;
+        vars.retain(|(existing, _)| existing != key);
+        vars.push((key.to_string(), value));
+    }
+    Ok(vars)
+}
+
+fn parse_value(value: &str) -> Result<String, String> {
+    let (parsed, rest) = if let Some(quoted) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = quoted.char_indices();
+        loop {
+            match chars.next() {
+                Some((i, '"')) => break (parsed, &quoted[i + 1..]),
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, 'n')) => parsed.push('\n'),
+                    Some((_, c)) => parsed.push(c),
+                    None => return Err("的双引号未闭合".to_string()),
+                },
+                Some((_, c)) => parsed.push(c),
+                None => return Err("的双引号未闭合".to_string()),
+            }
+        }
+    } else if let Some(quoted) = value.strip_prefix('\'') {
+        let end = quoted.find('\'').ok_or("的单引号未闭合")?;
+        (quoted[..end].to_string(), &quoted[end + 1..])
+    } else {
+        let end = value
+            .char_indices()
+            .find(|&(i, c)| c == '#' && value[..i].ends_with(char::is_whitespace))
+            .map_or(value.len(), |(i, _)| i);
+        return Ok(value[..end].trim_end().to_string());
+    };
+    let rest = rest.trim_start();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("的引号之后有多余内容: {:?}", rest));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_quoted_and_commented_lines() {
+        let content = r#"
+# 数据库
+DB_HOST=localhost
+export DB_PORT = 5432 # 默认端口
+DB_URL=postgres://db/app#main
+DB_PASSWORD="p@ss \"word\"\n" # 注释
+DB_OPTIONS='-c search_path=app # literal'
+EMPTY=
+DB_HOST=db.internal
+"#;
+        let vars = parse(content).unwrap();
+        assert_eq!(
+            vars,
+            [
+                ("DB_PORT", "5432"),
+                ("DB_URL", "postgres://db/app#main"),
+                ("DB_PASSWORD", "p@ss \"word\"\n"),
+                ("DB_OPTIONS", "-c search_path=app # literal"),
+                ("EMPTY", ""),
+                ("DB_HOST", "db.internal"),
+            ]
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+        );
+    }
+
+    #[test]
+    fn malformed_lines_report_their_line_number() {
+        assert!(parse("A=1\nB").unwrap_err().contains("第 2 行"));
+        assert!(parse("=1").is_err());
+        assert!(parse("A=\"open").is_err());
+        assert!(parse("A='open").is_err());
+        assert!(parse("A=\"x\" y").is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::process_match;
+
+/// 服务配置校验与启动过程中可识别的错误
+#[derive(Debug, Clone)]
+pub enum ServiceError {
+    /// 健康检查 URL 无法解析
+    InvalidHealthUrl(String),
+    /// 工作目录不存在
+    WorkingDirMissing(PathBuf),
+    /// 工作目录路径存在但不是目录
+    WorkingDirNotDirectory(PathBuf),
+    /// 可执行文件不存在
+    ExecutableMissing { executable: String, path: PathBuf },
+    /// shell 模式下命令行为空
+    EmptyShellCommand,
+    /// 健康检查方式为 file 但未配置就绪标记文件
+    MissingReadyFile,
+    /// 健康检查方式为 tcp 但地址未配置或不是 主机名:端口 形式
+    InvalidTcpAddress(String),
+    /// 健康检查方式为 log 但 ready_pattern 未配置或无法编译，附带原因
+    InvalidReadyPattern(String),
+    /// 健康检查方式为 log 但服务输出未被捕获（窗口模式不是 hidden）
+    ReadyLogNotCaptured,
+    /// cleanup_patterns 中的模式过宽，可能误杀无关进程
+    CleanupPatternTooBroad(String),
+    /// 该服务已有启动、停止或重启操作在进行中
+    Busy(String),
+    /// secret:// 引用的机密无法读取，只记录机密名称
+    SecretUnavailable(String),
+    /// umask 不是 0 到 0777 之间的八进制数
+    InvalidUmask(String),
+    /// 独立运行的服务不捕获输出，无法使用 log 健康检查
+    DetachedReadyLog,
+    /// 独立运行的服务标准输入为空，无法使用 pipe_stdin
+    DetachedPipeStdin,
+}
+
+impl ServiceError {
+    /// 是否属于配置错误（其余为启动失败），用于区分退出码
+    pub fn is_config_error(&self) -> bool {
+        matches!(
+            self,
+            ServiceError::InvalidHealthUrl(_)
+                | ServiceError::EmptyShellCommand
+                | ServiceError::MissingReadyFile
+                | ServiceError::InvalidTcpAddress(_)
+                | ServiceError::InvalidReadyPattern(_)
+                | ServiceError::ReadyLogNotCaptured
+                | ServiceError::CleanupPatternTooBroad(_)
+                | ServiceError::InvalidUmask(_)
+                | ServiceError::DetachedReadyLog
+                | ServiceError::DetachedPipeStdin
+        )
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceError::InvalidHealthUrl(message) => write!(f, "{}", message),
+            ServiceError::WorkingDirMissing(path) => write!(f, "工作目录不存在: {:?}", path),
+            ServiceError::WorkingDirNotDirectory(path) => {
+                write!(f, "工作目录不是目录: {:?}", path)
+            }
+            ServiceError::ExecutableMissing { executable, path } => {
+                write!(f, "{} 不存在于路径: {:?}", executable, path)
+            }
+            ServiceError::EmptyShellCommand => write!(f, "shell 模式下 executable 不能为空"),
+            ServiceError::MissingReadyFile => {
+                write!(f, "健康检查方式为 file 时必须配置 health_check.file")
+            }
+            ServiceError::InvalidTcpAddress(address) if address.is_empty() => {
+                write!(f, "健康检查方式为 tcp 时必须配置 health_check.address")
+            }
+            ServiceError::InvalidTcpAddress(address) => {
+                write!(f, "TCP 健康检查地址必须为 主机名:端口 形式: {}", address)
+            }
+            ServiceError::InvalidReadyPattern(reason) => {
+                write!(f, "健康检查方式为 log 时 ready_pattern 无效: {}", reason)
+            }
+            ServiceError::ReadyLogNotCaptured => write!(
+                f,
+                "健康检查方式为 log 时 window_mode 必须为 hidden，否则无法读取服务输出"
+            ),
+            ServiceError::CleanupPatternTooBroad(pattern) => write!(
+                f,
+                "cleanup_patterns 中的模式过宽，至少需要 {} 个非通配符字符: {}",
+                process_match::MIN_PATTERN_LITERALS,
+                pattern
+            ),
+            ServiceError::Busy(name) => write!(f, "{} 服务正在执行其他操作，请稍后重试", name),
+            ServiceError::SecretUnavailable(name) => write!(f, "无法读取机密: {}", name),
+            ServiceError::InvalidUmask(umask) => {
+                write!(f, "umask 必须是 0 到 0777 之间的八进制数: {}", umask)
+            }
+            ServiceError::DetachedReadyLog => write!(
+                f,
+                "独立运行（detached 或 --detach）的服务不捕获输出，不能使用 log 健康检查"
+            ),
+            ServiceError::DetachedPipeStdin => write!(
+                f,
+                "独立运行（detached 或 --detach）的服务不能使用 pipe_stdin"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+/// 创建进程失败是否可能是暂时的：Windows 上刚终止旧进程后可执行文件仍被杀毒软件扫描占用，
+/// 会返回共享冲突、锁冲突或拒绝访问；其他平台为 ETXTBSY。文件不存在等错误不属于此类
+pub fn is_transient_spawn_error(error: &std::io::Error) -> bool {
+    #[cfg(windows)]
+    {
+        const ERROR_ACCESS_DENIED: i32 = 5;
+        const ERROR_SHARING_VIOLATION: i32 = 32;
+        const ERROR_LOCK_VIOLATION: i32 = 33;
+        matches!(
+            error.raw_os_error(),
+            Some(ERROR_ACCESS_DENIED | ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+        )
+    }
+    #[cfg(not(windows))]
+    {
+        error.kind() == std::io::ErrorKind::ExecutableFileBusy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn only_locked_executable_errors_are_transient() {
+        assert!(!is_transient_spawn_error(&io::Error::from(
+            io::ErrorKind::NotFound
+        )));
+        #[cfg(windows)]
+        assert!(is_transient_spawn_error(&io::Error::from_raw_os_error(32)));
+        #[cfg(unix)]
+        assert!(is_transient_spawn_error(&io::Error::from_raw_os_error(
+            libc::ETXTBSY
+        )));
+    }
+}
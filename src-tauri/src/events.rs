@@ -0,0 +1,333 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::{error, trace};
+
+use crate::log_level::LogLevel;
+
+/// 后端发送给前端的全部事件，事件名只在这里定义
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServiceEvent {
+    /// 服务开始启动
+    Starting,
+    /// 服务健康检查通过
+    Ready,
+    /// 服务尚未就绪，健康检查响应中报告了启动进度
+    Progress,
+    /// 配置错误、启动失败或健康检查超时
+    Error,
+    /// 启动条件不满足，服务被跳过
+    Skipped,
+    /// 一批服务日志，按固定间隔合并发送
+    LogBatch,
+    /// 服务生命周期状态发生迁移
+    StateChanged,
+    /// recheck_service 命令完成了一次健康检查
+    Rechecked,
+    /// 后端心跳，前端据此判断后端是否失去响应
+    Heartbeat,
+    /// 整体启动超时，启动已中止
+    StartupTimeout,
+    /// 所有服务的启动流程已结束（无论成功与否）
+    StartupComplete,
+    /// 启动服务前等待网络可用，每次探测失败发送一次
+    WaitingForNetwork,
+    /// 存在当前权限无法终止的同名进程
+    PrivilegeWarning,
+    /// 服务已停止，附带停止原因
+    Stopped,
+    /// 配置文件可以加载但存在需要注意的问题
+    ConfigWarning,
+    /// 服务已进入排空模式，不再接受新请求
+    Draining,
+    /// 服务进入维护模式，暂停进程监视
+    MonitoringPaused,
+    /// 服务退出维护模式，恢复进程监视
+    MonitoringResumed,
+    /// 重新加载后服务已不在配置中，已被停止并移除
+    Removed,
+    /// 服务短时间内反复崩溃，已停止自动重启
+    RestartCircuitOpen,
+    /// 已熔断的自动重启通过 reset_restart_circuit 恢复
+    RestartCircuitReset,
+    /// 检测到重复启动且配置为 replace，当前实例即将退出并由新实例替换
+    InstanceReplacing,
+    /// 启动时查找配置文件的结果，列出所有候选路径
+    ConfigSearch,
+    /// xcontrol 自身或某个服务的日志级别已通过 set_log_level 调整
+    LogLevelChanged,
+}
+
+impl AsRef<str> for ServiceEvent {
+    fn as_ref(&self) -> &str {
+        match self {
+            ServiceEvent::Starting => "service_starting",
+            ServiceEvent::Ready => "service_ready",
+            ServiceEvent::Progress => "service_progress",
+            ServiceEvent::Error => "service_error",
+            ServiceEvent::Skipped => "service_skipped",
+            ServiceEvent::LogBatch => "service_log_batch",
+            ServiceEvent::StateChanged => "service_state_changed",
+            ServiceEvent::Rechecked => "service_rechecked",
+            ServiceEvent::Heartbeat => "backend_heartbeat",
+            ServiceEvent::StartupTimeout => "startup_timeout",
+            ServiceEvent::StartupComplete => "startup_complete",
+            ServiceEvent::WaitingForNetwork => "waiting_for_network",
+            ServiceEvent::PrivilegeWarning => "privilege_warning",
+            ServiceEvent::Stopped => "service_stopped",
+            ServiceEvent::ConfigWarning => "config_warning",
+            ServiceEvent::Draining => "service_draining",
+            ServiceEvent::MonitoringPaused => "monitoring_paused",
+            ServiceEvent::MonitoringResumed => "monitoring_resumed",
+            ServiceEvent::Removed => "service_removed",
+            ServiceEvent::RestartCircuitOpen => "service_restart_circuit_open",
+            ServiceEvent::RestartCircuitReset => "service_restart_circuit_reset",
+            ServiceEvent::InstanceReplacing => "instance_replacing",
+            ServiceEvent::ConfigSearch => "config_search",
+            ServiceEvent::LogLevelChanged => "log_level_changed",
+        }
+    }
+}
+
+/// 服务状态事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct ServiceEventData {
+    pub service_name: String,
+    pub url: String,
+    pub public_url: String, // 仅 service_ready 事件填写，供前端打开或跳转
+    pub error: String,
+    pub status: String,   // "starting", "ready", "error", "skipped_condition"
+    pub pid: Option<u32>, // 服务主进程 PID，进程尚未启动或未知时为 None
+}
+
+/// service_ready 事件的数据结构，details 为健康检查响应中按 export 提取的字段
+#[derive(Serialize, Clone)]
+pub struct ServiceReadyData {
+    #[serde(flatten)]
+    pub event: ServiceEventData,
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    pub details: serde_json::Value,
+}
+
+/// service_progress 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct ServiceProgressData {
+    pub service_name: String,
+    pub progress: f64, // 0 到 1
+}
+
+/// service_rechecked 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct ServiceRecheckedData {
+    pub service_name: String,
+    pub healthy: bool,
+}
+
+/// backend_heartbeat 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct HeartbeatData {
+    pub counter: u64,
+    pub timestamp_ms: u64,
+}
+
+/// startup_timeout 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct StartupTimeoutData {
+    pub timeout_ms: u64,
+    pub terminated: Vec<String>, // 因超时被终止的服务
+}
+
+/// startup_complete 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct StartupCompleteData {
+    pub exit_code: i32, // 与 --verify-only 的退出码含义相同
+}
+
+/// waiting_for_network 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct WaitingForNetworkData {
+    pub target: String,
+    pub elapsed_ms: u64,
+    pub timeout_ms: u64,
+    pub error: String, // 最近一次探测失败的原因
+}
+
+/// privilege_warning 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct PrivilegeWarningData {
+    pub service_name: String,
+    pub pids: Vec<u32>, // 当前权限无法终止的进程
+    pub elevated: bool,
+}
+
+/// config_warning 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct ConfigWarningData {
+    pub message: String,
+}
+
+/// config_search 事件中的一个候选路径
+#[derive(Serialize, Clone)]
+pub struct ConfigCandidate {
+    pub path: String, // 绝对路径
+    pub exists: bool,
+}
+
+/// config_search 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct ConfigSearchData {
+    pub candidates: Vec<ConfigCandidate>, // 按查找顺序排列
+    pub found: Option<String>,            // 实际使用的配置文件，均不存在时为 None
+}
+
+/// log_level_changed 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct LogLevelChangedData {
+    pub service_name: Option<String>, // None 表示 xcontrol 自身
+    pub level: LogLevel,
+}
+
+/// service_draining 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct ServiceDrainingData {
+    pub service_name: String,
+    pub drain_wait_ms: u64, // 停止前的等待时间；由 drain_service 命令触发时不会自动停止
+}
+
+/// monitoring_paused / monitoring_resumed 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct MonitoringData {
+    pub services: Vec<String>, // 状态发生变化的服务
+}
+
+/// 服务停止的原因
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// 用户通过命令停止、重启或更新
+    User,
+    /// 应用退出或启动中止
+    Shutdown,
+    /// 进程意外退出
+    Crashed,
+    /// 所依赖的服务重启，随之重启
+    Dependency,
+    /// 超过 idle_timeout_ms 未被使用
+    Idle,
+    /// 超出资源限制被终止
+    ResourceLimit,
+}
+
+/// service_stopped 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct ServiceStoppedData {
+    pub service_name: String,
+    pub reason: StopReason,
+}
+
+/// service_removed 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct ServiceRemovedData {
+    pub service_name: String,
+}
+
+/// service_restart_circuit_open 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct RestartCircuitOpenData {
+    pub service_name: String,
+    pub restart_burst_limit: u32,
+    pub restart_burst_window_ms: u64,
+}
+
+/// service_restart_circuit_reset 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct RestartCircuitResetData {
+    pub service_name: String,
+    pub was_open: bool,   // 恢复前自动重启是否已熔断
+    pub relaunched: bool, // 服务未在运行，已按当前配置重新启动
+}
+
+/// instance_replacing 事件的数据结构，args 为新实例的命令行参数
+#[derive(Serialize, Clone)]
+pub struct InstanceReplacingData {
+    pub args: Vec<String>,
+}
+
+/// 事件的发送目标：应用中为 AppHandle 或 WindowTarget，测试中可替换为记录事件的实现
+pub trait EventEmitter {
+    fn emit_event(&self, event: &str, payload: serde_json::Value) -> Result<(), String>;
+}
+
+/// 发送给所有窗口。服务事件都通过 AppHandle 广播，启动窗口、主窗口与重新创建的主窗口都能收到，
+/// 不会因为某个窗口即将关闭而丢失
+impl<R: Runtime> EventEmitter for AppHandle<R> {
+    fn emit_event(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        self.emit(event, payload).map_err(|e| e.to_string())
+    }
+}
+
+/// 只发送给指定 label 的窗口，用于仅与该窗口相关、其他窗口不应处理的事件
+pub struct WindowTarget<'a, R: Runtime> {
+    pub app: &'a AppHandle<R>,
+    pub label: &'a str,
+}
+
+impl<R: Runtime> EventEmitter for WindowTarget<'_, R> {
+    fn emit_event(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        self.app
+            .emit_to(self.label, event, payload)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// 向前端发送事件，所有发送点都应通过此函数
+pub fn emit_service_event<S: Serialize>(emitter: &impl EventEmitter, event: ServiceEvent, data: S) {
+    let result = serde_json::to_value(data)
+        .map_err(|e| e.to_string())
+        .and_then(|payload| {
+            trace!("发送 {} 事件: {}", event.as_ref(), payload);
+            emitter.emit_event(event.as_ref(), payload)
+        });
+    if let Err(e) = result {
+        error!("发送 {} 事件失败: {}", event.as_ref(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// 记录发送的事件，代替 Tauri 窗口
+    #[derive(Default)]
+    struct RecordingEmitter {
+        events: RefCell<Vec<(String, serde_json::Value)>>,
+    }
+
+    impl EventEmitter for RecordingEmitter {
+        fn emit_event(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+            self.events.borrow_mut().push((event.to_string(), payload));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn events_are_sent_with_their_wire_name() {
+        let emitter = RecordingEmitter::default();
+        emit_service_event(
+            &emitter,
+            ServiceEvent::Stopped,
+            ServiceStoppedData {
+                service_name: "api".to_string(),
+                reason: StopReason::Crashed,
+            },
+        );
+
+        let events = emitter.events.borrow();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "service_stopped");
+        assert_eq!(
+            events[0].1,
+            serde_json::json!({"service_name": "api", "reason": "crashed"})
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, Endpoint};
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+
+/// HealthCheckResponse 中的服务状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServingStatus {
+    Unknown,
+    Serving,
+    NotServing,
+    ServiceUnknown,
+}
+
+impl ServingStatus {
+    fn from_value(value: i32) -> Self {
+        match value {
+            1 => ServingStatus::Serving,
+            2 => ServingStatus::NotServing,
+            3 => ServingStatus::ServiceUnknown,
+            _ => ServingStatus::Unknown,
+        }
+    }
+}
+
+/// 创建到 gRPC 服务的明文 HTTP/2 通道，首次调用时才建立连接，断开后自动重连；
+/// 超时为 None 时不限时
+pub fn channel(
+    url: &str,
+    connect_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+) -> Result<Channel, String> {
+    let mut endpoint =
+        Endpoint::from_shared(url.to_string()).map_err(|e| format!("无效的 gRPC 地址: {}", e))?;
+    if let Some(timeout) = connect_timeout {
+        endpoint = endpoint.connect_timeout(timeout);
+    }
+    if let Some(timeout) = request_timeout {
+        endpoint = endpoint.timeout(timeout);
+    }
+    Ok(endpoint.connect_lazy())
+}
+
+/// 调用标准 gRPC 健康检查协议（grpc.health.v1）的 Check 方法，service 为空时检查整个服务器；
+/// 服务端返回非 OK 的 grpc-status（如未登记的服务为 NotFound）时返回错误
+pub async fn check(
+    channel: Channel,
+    service: &str,
+    auth_bearer: Option<&str>,
+) -> Result<ServingStatus, String> {
+    let mut request = tonic::Request::new(HealthCheckRequest {
+        service: service.to_string(),
+    });
+    if let Some(token) = auth_bearer {
+        let value = MetadataValue::try_from(format!("Bearer {}", token))
+            .map_err(|_| "auth_bearer 含有无法放入请求头的字符".to_string())?;
+        request.metadata_mut().insert("authorization", value);
+    }
+    let response = HealthClient::new(channel)
+        .check(request)
+        .await
+        .map_err(|status| format!("grpc-status {:?}: {}", status.code(), status.message()))?;
+    Ok(ServingStatus::from_value(response.into_inner().status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::transport::server::TcpIncoming;
+
+    #[tokio::test]
+    async fn checks_standard_health_service() {
+        let (reporter, health_service) = tonic_health::server::health_reporter();
+        reporter
+            .set_service_status("api", tonic_health::ServingStatus::Serving)
+            .await;
+        reporter
+            .set_service_status("batch", tonic_health::ServingStatus::NotServing)
+            .await;
+        let incoming = TcpIncoming::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let url = format!("http://{}", incoming.local_addr().unwrap());
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(health_service)
+                .serve_with_incoming(incoming),
+        );
+
+        let timeout = Some(Duration::from_secs(5));
+        let health = channel(&url, timeout, timeout).unwrap();
+        assert_eq!(
+            check(health.clone(), "", Some("token")).await,
+            Ok(ServingStatus::Serving)
+        );
+        assert_eq!(
+            check(health.clone(), "api", None).await,
+            Ok(ServingStatus::Serving)
+        );
+        assert_eq!(
+            check(health.clone(), "batch", None).await,
+            Ok(ServingStatus::NotServing)
+        );
+        // 未登记的服务以 grpc-status NotFound 回应，不是解码错误
+        let error = check(health, "missing", None).await.unwrap_err();
+        assert!(error.starts_with("grpc-status NotFound"), "{}", error);
+
+        assert!(channel("not a url", None, None).is_err());
+    }
+}
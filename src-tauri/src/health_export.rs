@@ -0,0 +1,112 @@
+use reqwest::header::HeaderMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 健康检查通过时从响应中提取的字段，随 service_ready 事件的 details 发送给前端
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default)]
+pub struct HealthExport {
+    #[serde(default)]
+    pub headers: HashMap<String, String>, // 字段名 -> 响应头名称
+    #[serde(default)]
+    pub json: HashMap<String, String>, // 字段名 -> 响应体中的 JSON 指针，如 /data/port
+}
+
+impl HealthExport {
+    /// 是否需要读取响应体
+    pub fn needs_body(&self) -> bool {
+        !self.json.is_empty()
+    }
+
+    /// 提取所有配置的字段，缺失的字段为 null；响应体不是 JSON 时 json 中的字段均为 null
+    pub fn extract(&self, headers: &HeaderMap, body: &[u8]) -> Value {
+        let mut details = serde_json::Map::new();
+        for (field, header) in &self.headers {
+            let value = headers
+                .get(header.as_str())
+                .and_then(|value| value.to_str().ok())
+                .map(|value| Value::String(value.to_string()))
+                .unwrap_or(Value::Null);
+            details.insert(field.clone(), value);
+        }
+
+        if self.needs_body() {
+            let body: Option<Value> = serde_json::from_slice(body).ok();
+            for (field, pointer) in &self.json {
+                let value = body
+                    .as_ref()
+                    .and_then(|body| body.pointer(pointer))
+                    .cloned()
+                    .unwrap_or(Value::Null);
+                details.insert(field.clone(), value);
+            }
+        }
+
+        Value::Object(details)
+    }
+}
+
+/// 从未就绪时的健康检查响应体中读取启动进度，限制在 0 到 1 之间；无法读取时返回 None
+pub fn read_progress(body: &[u8], pointer: &str) -> Option<f64> {
+    let body: Value = serde_json::from_slice(body).ok()?;
+    let progress = body.pointer(pointer)?.as_f64()?;
+    Some(progress.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn export(headers: &[(&str, &str)], json: &[(&str, &str)]) -> HealthExport {
+        let map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        HealthExport {
+            headers: map(headers),
+            json: map(json),
+        }
+    }
+
+    #[test]
+    fn headers_and_json_pointers_are_extracted() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-session-token", "abc".parse().unwrap());
+        let body = br#"{"data": {"port": 9861, "tags": ["a"]}}"#;
+
+        let details = export(
+            &[("token", "X-Session-Token")],
+            &[("port", "/data/port"), ("first_tag", "/data/tags/0")],
+        )
+        .extract(&headers, body);
+        assert_eq!(
+            details,
+            json!({"token": "abc", "port": 9861, "first_tag": "a"})
+        );
+    }
+
+    #[test]
+    fn progress_is_read_and_clamped() {
+        assert_eq!(
+            read_progress(br#"{"progress": 0.4}"#, "/progress"),
+            Some(0.4)
+        );
+        assert_eq!(
+            read_progress(br#"{"status": {"progress": 1.5}}"#, "/status/progress"),
+            Some(1.0)
+        );
+        assert_eq!(read_progress(br#"{"progress": "40%"}"#, "/progress"), None);
+        assert_eq!(read_progress(b"starting", "/progress"), None);
+    }
+
+    #[test]
+    fn missing_fields_are_null() {
+        let details = export(&[("token", "X-Missing")], &[("port", "/port")])
+            .extract(&HeaderMap::new(), b"not json");
+        assert_eq!(details, json!({"token": null, "port": null}));
+    }
+}
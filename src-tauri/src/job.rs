@@ -0,0 +1,221 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 服务进程树的资源限制，仅 Windows 通过 Job Object 生效
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default)]
+pub struct ResourceLimits {
+    #[serde(default)]
+    pub memory_limit_bytes: Option<u64>, // 整个进程树的提交内存上限，超出后终止服务
+    #[serde(default)]
+    pub cpu_rate_percent: Option<u32>, // CPU 占用硬上限（占全部 CPU 的百分比，1-100）
+}
+
+#[cfg(windows)]
+pub use windows::{attach, close, limit_exceeded};
+
+/// 非 Windows 平台不支持 Job Object，配置了资源限制时只给出提示
+#[cfg(not(windows))]
+pub fn attach(service_name: &str, _pid: u32, limits: Option<&ResourceLimits>) {
+    if limits.is_some_and(|l| l.memory_limit_bytes.is_some() || l.cpu_rate_percent.is_some()) {
+        tracing::warn!(
+            "{} 服务配置了资源限制，但当前平台不支持，已忽略",
+            service_name
+        );
+    }
+}
+
+#[cfg(not(windows))]
+pub fn close(_service_name: &str) {}
+
+#[cfg(not(windows))]
+pub fn limit_exceeded(_service_name: &str) -> bool {
+    false
+}
+
+#[cfg(windows)]
+mod windows {
+    use super::ResourceLimits;
+    use std::collections::HashMap;
+    use std::ptr::null_mut;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::error;
+    use winapi::shared::basetsd::ULONG_PTR;
+    use winapi::shared::minwindef::{DWORD, LPVOID};
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::ioapiset::{CreateIoCompletionPort, GetQueuedCompletionStatus};
+    use winapi::um::jobapi2::{
+        AssignProcessToJobObject, CreateJobObjectW, SetInformationJobObject, TerminateJobObject,
+    };
+    use winapi::um::minwinbase::LPOVERLAPPED;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::winbase::INFINITE;
+    use winapi::um::winnt::{
+        JobObjectAssociateCompletionPortInformation, JobObjectCpuRateControlInformation,
+        JobObjectExtendedLimitInformation, HANDLE, JOBOBJECT_ASSOCIATE_COMPLETION_PORT,
+        JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_CPU_RATE_CONTROL_ENABLE, JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+        JOB_OBJECT_LIMIT_JOB_MEMORY, JOB_OBJECT_MSG_JOB_MEMORY_LIMIT, PROCESS_SET_QUOTA,
+        PROCESS_TERMINATE,
+    };
+
+    /// 因超出资源限制被终止的进程退出码
+    const RESOURCE_LIMIT_EXIT_CODE: u32 = 0xC000_0017; // STATUS_NO_MEMORY
+
+    /// 内核句柄，在线程间传递只涉及句柄值本身
+    struct Handle(HANDLE);
+
+    unsafe impl Send for Handle {}
+    unsafe impl Sync for Handle {}
+
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    struct ServiceJob {
+        job: Arc<Handle>,
+        // 关闭完成端口后监视线程退出
+        _port: Handle,
+        limit_exceeded: Arc<AtomicBool>,
+    }
+
+    /// 各服务当前的 Job Object，服务停止时关闭
+    static JOBS: Mutex<Option<HashMap<String, ServiceJob>>> = Mutex::new(None);
+
+    /// 把服务主进程放入新的 Job Object 并应用资源限制，之后派生的子进程自动加入同一 Job
+    pub fn attach(service_name: &str, pid: u32, limits: Option<&ResourceLimits>) {
+        match create_job(pid, limits.cloned().unwrap_or_default()) {
+            Ok(job) => {
+                let mut jobs = JOBS.lock().unwrap();
+                jobs.get_or_insert_with(HashMap::new)
+                    .insert(service_name.to_string(), job);
+            }
+            Err(e) => error!("为 {} 服务创建 Job Object 失败: {}", service_name, e),
+        }
+    }
+
+    /// 终止 Job 中剩余的所有进程（整个进程树）并释放 Job
+    pub fn close(service_name: &str) {
+        let job = JOBS
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|jobs| jobs.remove(service_name));
+        if let Some(job) = job {
+            unsafe { TerminateJobObject(job.job.0, 1) };
+        }
+    }
+
+    /// 服务是否因超出资源限制被 Job 终止
+    pub fn limit_exceeded(service_name: &str) -> bool {
+        JOBS.lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|jobs| jobs.get(service_name))
+            .is_some_and(|job| job.limit_exceeded.load(Ordering::SeqCst))
+    }
+
+    fn create_job(pid: u32, limits: ResourceLimits) -> std::io::Result<ServiceJob> {
+        let last_error = std::io::Error::last_os_error;
+
+        let job = unsafe { CreateJobObjectW(null_mut(), std::ptr::null()) };
+        if job.is_null() {
+            return Err(last_error());
+        }
+        let job = Arc::new(Handle(job));
+
+        let port = unsafe { CreateIoCompletionPort(INVALID_HANDLE_VALUE, null_mut(), 0, 1) };
+        if port.is_null() {
+            return Err(last_error());
+        }
+        let port = Handle(port);
+
+        let mut association = JOBOBJECT_ASSOCIATE_COMPLETION_PORT {
+            CompletionKey: null_mut(),
+            CompletionPort: port.0,
+        };
+        set_information(
+            &job,
+            JobObjectAssociateCompletionPortInformation,
+            &mut association,
+        )?;
+
+        if let Some(memory_limit) = limits.memory_limit_bytes {
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_JOB_MEMORY;
+            info.JobMemoryLimit = memory_limit as usize;
+            set_information(&job, JobObjectExtendedLimitInformation, &mut info)?;
+        }
+
+        if let Some(percent) = limits.cpu_rate_percent {
+            let mut info: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION = unsafe { std::mem::zeroed() };
+            info.ControlFlags =
+                JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+            // 单位为万分之一
+            unsafe { *info.u.CpuRate_mut() = percent.clamp(1, 100) * 100 };
+            set_information(&job, JobObjectCpuRateControlInformation, &mut info)?;
+        }
+
+        let process = unsafe { OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid) };
+        if process.is_null() {
+            return Err(last_error());
+        }
+        let process = Handle(process);
+        if unsafe { AssignProcessToJobObject(job.0, process.0) } == 0 {
+            return Err(last_error());
+        }
+
+        let limit_exceeded = Arc::new(AtomicBool::new(false));
+        spawn_limit_watcher(port.0 as usize, job.clone(), limit_exceeded.clone());
+
+        Ok(ServiceJob {
+            job,
+            _port: port,
+            limit_exceeded,
+        })
+    }
+
+    fn set_information<T>(job: &Handle, class: u32, info: &mut T) -> std::io::Result<()> {
+        let ok = unsafe {
+            SetInformationJobObject(
+                job.0,
+                class,
+                info as *mut T as LPVOID,
+                std::mem::size_of::<T>() as DWORD,
+            )
+        };
+        if ok == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// 等待 Job 的内存超限通知：超出上限时内存分配失败，由这里终止整个进程树，
+    /// 进程退出后由崩溃检测读取 limit_exceeded 上报
+    fn spawn_limit_watcher(port: usize, job: Arc<Handle>, limit_exceeded: Arc<AtomicBool>) {
+        std::thread::spawn(move || loop {
+            let mut message: DWORD = 0;
+            let mut key: ULONG_PTR = 0;
+            let mut overlapped: LPOVERLAPPED = null_mut();
+            let ok = unsafe {
+                GetQueuedCompletionStatus(
+                    port as HANDLE,
+                    &mut message,
+                    &mut key,
+                    &mut overlapped,
+                    INFINITE,
+                )
+            };
+            // 完成端口被关闭
+            if ok == 0 {
+                return;
+            }
+            if message == JOB_OBJECT_MSG_JOB_MEMORY_LIMIT {
+                limit_exceeded.store(true, Ordering::SeqCst);
+                unsafe { TerminateJobObject(job.0, RESOURCE_LIMIT_EXIT_CODE) };
+            }
+        });
+    }
+}
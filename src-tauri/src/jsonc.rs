@@ -0,0 +1,80 @@
+/// 去掉 JSON 文本中的 `//` 行注释和 `/* */` 块注释，字符串内的内容保持不变
+///
+/// 注释替换为空白（块注释保留换行），解析错误报告的行号与原文一致
+pub fn strip_comments(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    if c == '\n' {
+                        output.push('\n');
+                    }
+                    previous = c;
+                }
+                output.push(' ');
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_are_removed() {
+        let input = "{\n  // 注释\n  \"a\": 1, /* 块\n注释 */ \"b\": 2\n}";
+        let value: serde_json::Value = serde_json::from_str(&strip_comments(input)).unwrap();
+        assert_eq!(value, serde_json::json!({"a": 1, "b": 2}));
+    }
+
+    #[test]
+    fn comment_markers_inside_strings_are_kept() {
+        let input = r#"{"url": "http://127.0.0.1/*x*/", "path": "a\"//b"}"#;
+        assert_eq!(strip_comments(input), input);
+    }
+
+    #[test]
+    fn line_numbers_are_preserved() {
+        let input = "/* 1\n2 */\n// 3\n{}";
+        assert_eq!(strip_comments(input).lines().count(), input.lines().count());
+    }
+}
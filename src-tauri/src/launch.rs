@@ -0,0 +1,137 @@
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio_util::sync::CancellationToken;
+
+/// 启动间隔加上不超过其一半的抖动，避免多个实例以相同节奏启动
+pub fn start_stagger_delay(stagger_ms: u64) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or_default();
+    Duration::from_millis(stagger_ms + nanos % (stagger_ms / 2 + 1))
+}
+
+/// 并发执行同一批启动任务：第 i 个任务在第 i-1 个开始后再等待 next_delay() 才开始，
+/// 前面的任务不必先完成。开始前 cancel 已取消的任务不再执行，结果为 None；结果与 items 顺序一致。
+/// 所有任务在同一个 future 中轮询，launch 中的阻塞操作须放到阻塞线程池执行，否则会推迟后续任务的开始
+pub async fn run_staggered<T, F, Fut>(
+    items: Vec<T>,
+    mut next_delay: impl FnMut() -> Duration,
+    cancel: &CancellationToken,
+    launch: F,
+) -> Vec<Option<Fut::Output>>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future,
+{
+    let launch = &launch;
+    let mut offset = Duration::ZERO;
+    let launches = items.into_iter().enumerate().map(|(index, item)| {
+        if index > 0 {
+            offset += next_delay();
+        }
+        let start = offset;
+        async move {
+            if !start.is_zero() {
+                tokio::select! {
+                    _ = tokio::time::sleep(start) => {}
+                    _ = cancel.cancelled() => return None,
+                }
+            }
+            if cancel.is_cancelled() {
+                return None;
+            }
+            Some(launch(item).await)
+        }
+    });
+    futures::future::join_all(launches).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn tasks_in_a_tier_overlap() {
+        let probe = |name: &'static str| async move {
+            let started = Instant::now();
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            (name, started, Instant::now())
+        };
+        let results = run_staggered(
+            vec!["db", "cache"],
+            || Duration::ZERO,
+            &CancellationToken::new(),
+            probe,
+        )
+        .await;
+
+        let (first, first_start, first_end) = results[0].unwrap();
+        let (second, second_start, second_end) = results[1].unwrap();
+        assert_eq!((first, second), ("db", "cache"));
+        // 第二个探测在第一个结束前已经开始
+        assert!(second_start < first_end && first_start < second_end);
+    }
+
+    #[tokio::test]
+    async fn blocking_launch_does_not_delay_next_start() {
+        let begin = Instant::now();
+        let results = run_staggered(
+            vec![Duration::from_millis(500), Duration::ZERO],
+            || Duration::from_millis(50),
+            &CancellationToken::new(),
+            |blocking| async move {
+                let started = begin.elapsed();
+                // 与创建服务进程一样，阻塞操作放在阻塞线程池中执行
+                tokio::task::spawn_blocking(move || std::thread::sleep(blocking))
+                    .await
+                    .unwrap();
+                started
+            },
+        )
+        .await;
+        let second_start = results[1].unwrap();
+        assert!(second_start >= Duration::from_millis(50));
+        assert!(
+            second_start < Duration::from_millis(500),
+            "{:?}",
+            second_start
+        );
+    }
+
+    #[tokio::test]
+    async fn stagger_offsets_starts_and_cancel_skips_pending_tasks() {
+        let begin = Instant::now();
+        let results = run_staggered(
+            vec![0, 1, 2],
+            || Duration::from_millis(100),
+            &CancellationToken::new(),
+            |index| async move { (index, begin.elapsed()) },
+        )
+        .await;
+        let starts: Vec<Duration> = results.iter().map(|r| r.unwrap().1).collect();
+        assert!(starts[0] < Duration::from_millis(100));
+        assert!(starts[1] >= Duration::from_millis(100));
+        assert!(starts[2] >= Duration::from_millis(200));
+
+        let cancel = CancellationToken::new();
+        let results = run_staggered(
+            vec![0, 1],
+            || Duration::from_secs(60),
+            &cancel,
+            |index| {
+                let cancel = cancel.clone();
+                async move {
+                    cancel.cancel();
+                    index
+                }
+            },
+        )
+        .await;
+        assert_eq!(results, [Some(0), None]);
+
+        let delay = start_stagger_delay(100);
+        assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(150));
+    }
+}
@@ -1,14 +1,45 @@
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
-}
+//! xcontrol 的核心逻辑：配置加载与校验、路径解析、依赖排序、日志解码与事件定义。
+//! 这里不依赖运行中的 Tauri 窗口，事件通过 [`events::EventEmitter`] 发送，可在单元测试中替换；
+//! 进程管理、前端命令与窗口相关的逻辑在可执行文件（main.rs）中。
+
+pub mod cert;
+pub mod cleanup;
+pub mod condition;
+pub mod config;
+pub mod drain;
+pub mod duration;
+pub mod env_file;
+pub mod error;
+pub mod events;
+pub mod grpc_health;
+pub mod health_export;
+pub mod job;
+pub mod jsonc;
+pub mod launch;
+pub mod log_level;
+pub mod logs;
+pub mod marker;
+pub mod metrics;
+pub mod network;
+pub mod precondition;
+pub mod priority;
+pub mod process_backend;
+pub mod process_match;
+pub mod ready_log;
+pub mod restart;
+pub mod signal;
+pub mod state;
+pub mod tcp_probe;
+pub mod update;
+pub mod watch;
+pub mod window;
+
+use std::time::{SystemTime, UNIX_EPOCH};
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+/// 当前 Unix 时间戳（毫秒）
+pub fn unix_timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use xcontrol_lib::error::ServiceError;
+
+/// 正在执行手动操作的服务集合：同一服务的操作互斥，不同服务互不影响
+#[derive(Default)]
+pub struct ServiceLocks {
+    busy: Mutex<HashSet<String>>,
+}
+
+pub type ServiceLockStore = Arc<ServiceLocks>;
+
+impl ServiceLocks {
+    /// 获取服务的操作锁，已有操作进行中时立即返回 Busy 而不排队等待
+    pub fn try_lock(
+        self: &Arc<Self>,
+        service_name: &str,
+    ) -> Result<ServiceLockGuard, ServiceError> {
+        let mut busy = self.busy.lock().unwrap();
+        if !busy.insert(service_name.to_string()) {
+            return Err(ServiceError::Busy(service_name.to_string()));
+        }
+        Ok(ServiceLockGuard {
+            locks: self.clone(),
+            service_name: service_name.to_string(),
+        })
+    }
+}
+
+/// 操作锁守卫，离开作用域时自动释放
+pub struct ServiceLockGuard {
+    locks: ServiceLockStore,
+    service_name: String,
+}
+
+impl Drop for ServiceLockGuard {
+    fn drop(&mut self) {
+        self.locks.busy.lock().unwrap().remove(&self.service_name);
+    }
+}
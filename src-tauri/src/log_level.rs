@@ -0,0 +1,125 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::level_filters::LevelFilter;
+
+/// 日志级别，越靠后输出越详细
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+const LEVELS: [LogLevel; 5] = [
+    LogLevel::Error,
+    LogLevel::Warn,
+    LogLevel::Info,
+    LogLevel::Debug,
+    LogLevel::Trace,
+];
+
+impl LogLevel {
+    /// 解析级别名称，不区分大小写，warning 视为 warn
+    pub fn parse(text: &str) -> Result<Self, String> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err(format!(
+                "无效的日志级别: {:?}，应为 error、warn、info、debug 或 trace",
+                text
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// 关闭日志（LevelFilter::OFF）时视为 error
+impl From<LevelFilter> for LogLevel {
+    fn from(filter: LevelFilter) -> Self {
+        LEVELS
+            .into_iter()
+            .rev()
+            .find(|&level| LevelFilter::from(level) <= filter)
+            .unwrap_or(LogLevel::Error)
+    }
+}
+
+/// 服务运行时调整日志级别的方式，url 与 signal 可同时配置，先请求 url 再发送信号
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct LogLevelConfig {
+    #[serde(default)]
+    pub url: Option<String>, // 收到 POST {"level": "debug"} 后切换级别，URL 中的 {level} 会被替换为级别名称
+    #[serde(default)]
+    pub signal: Option<String>, // 切换级别时发送的信号（如 HUP），服务通常据此重新读取配置；非 Windows 平台
+}
+
+/// 日志级别接口的请求超时
+const LOG_LEVEL_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 请求服务的日志级别接口，接口返回成功状态码视为成功
+pub async fn request(url: &str, level: LogLevel) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(LOG_LEVEL_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .post(url.replace("{level}", level.as_str()))
+        .json(&serde_json::json!({ "level": level }))
+        .send()
+        .await
+        .map_err(|e| format!("请求日志级别接口失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("日志级别接口返回状态码: {}", response.status()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_levels_and_converts_to_filters() {
+        assert_eq!(LogLevel::parse(" DEBUG "), Ok(LogLevel::Debug));
+        assert_eq!(LogLevel::parse("warning"), Ok(LogLevel::Warn));
+        assert!(LogLevel::parse("verbose").is_err());
+        for level in LEVELS {
+            assert_eq!(LogLevel::parse(level.as_str()), Ok(level));
+        }
+
+        for level in LEVELS {
+            assert_eq!(LogLevel::from(LevelFilter::from(level)), level);
+        }
+        assert_eq!(LevelFilter::from(LogLevel::Warn), LevelFilter::WARN);
+        assert_eq!(LogLevel::from(LevelFilter::OFF), LogLevel::Error);
+    }
+}
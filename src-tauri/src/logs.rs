@@ -0,0 +1,311 @@
+use encoding_rs::GBK;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, IsTerminal, Read};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+use crate::unix_timestamp_ms;
+
+/// 每个服务在内存中保留的最近日志行数
+const MAX_LOG_LINES: usize = 500;
+
+/// 单个批量事件中每个服务最多携带的日志行数，超出时丢弃最早的行并插入截断标记
+const MAX_LOG_BATCH_LINES: usize = 1000;
+
+/// 日志转发落后、跳过的行无法归属到服务时，截断标记行使用的服务名
+pub const SKIPPED_LOG_SERVICE: &str = "xcontrol";
+
+/// 区分服务的颜色，依次对应 ANSI 前景色 31-36，前端可直接作为 CSS 颜色名使用
+const SERVICE_COLORS: [&str; 6] = ["red", "green", "yellow", "blue", "magenta", "cyan"];
+
+/// 单行服务输出
+#[derive(Serialize, Clone, Debug)]
+pub struct LogLine {
+    pub service_name: String,
+    pub stream: String, // "stdout"、"stderr"，截断标记为 "xcontrol"
+    pub line: String,
+    pub timestamp_ms: u64,
+    pub prefix: String,      // "[服务名]"，多个服务的日志混在一起时用于区分
+    pub color: &'static str, // 按服务名固定分配的颜色
+}
+
+impl LogLine {
+    pub fn new(service_name: &str, stream: &str, line: String, timestamp_ms: u64) -> Self {
+        Self {
+            service_name: service_name.to_string(),
+            stream: stream.to_string(),
+            line,
+            timestamp_ms,
+            prefix: format!("[{}]", service_name),
+            color: service_color(service_name),
+        }
+    }
+
+    /// `[truncated N lines]` 截断标记行
+    pub fn truncated(service_name: &str, count: usize, timestamp_ms: u64) -> Self {
+        Self::new(
+            service_name,
+            "xcontrol",
+            format!("[truncated {} lines]", count),
+            timestamp_ms,
+        )
+    }
+
+    /// 输出到 xcontrol 控制台的文本，colored 为 true 时前缀带 ANSI 颜色
+    pub fn console_text(&self, colored: bool) -> String {
+        if !colored {
+            return format!("{} {}", self.prefix, self.line);
+        }
+        let index = SERVICE_COLORS
+            .iter()
+            .position(|&color| color == self.color)
+            .unwrap_or_default();
+        format!("\x1b[{}m{}\x1b[0m {}", 31 + index, self.prefix, self.line)
+    }
+}
+
+/// 按服务名的 FNV-1a 哈希分配颜色，不随运行或版本变化
+pub fn service_color(service_name: &str) -> &'static str {
+    let hash = service_name
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    SERVICE_COLORS[(hash % SERVICE_COLORS.len() as u64) as usize]
+}
+
+/// 服务日志缓冲区：前端命令和管理服务器共用同一份数据
+pub struct LogBuffer {
+    lines: Mutex<HashMap<String, VecDeque<LogLine>>>,
+    sender: broadcast::Sender<LogLine>,
+}
+
+pub type LogStore = Arc<LogBuffer>;
+
+impl LogBuffer {
+    pub fn new() -> LogStore {
+        let (sender, _) = broadcast::channel(1024);
+        Arc::new(Self {
+            lines: Mutex::new(HashMap::new()),
+            sender,
+        })
+    }
+
+    /// 写入一行日志并广播给所有订阅者
+    pub fn push(&self, line: LogLine) {
+        {
+            let mut lines = self.lines.lock().unwrap();
+            let buffer = lines.entry(line.service_name.clone()).or_default();
+            if buffer.len() >= MAX_LOG_LINES {
+                buffer.pop_front();
+            }
+            buffer.push_back(line.clone());
+        }
+        // 没有订阅者时发送失败属于正常情况
+        let _ = self.sender.send(line);
+    }
+
+    /// 获取指定服务最近的日志
+    pub fn recent(&self, service_name: &str) -> Option<Vec<LogLine>> {
+        let lines = self.lines.lock().unwrap();
+        lines
+            .get(service_name)
+            .map(|buffer| buffer.iter().cloned().collect())
+    }
+
+    /// 订阅实时日志流
+    pub fn subscribe(&self) -> broadcast::Receiver<LogLine> {
+        self.sender.subscribe()
+    }
+}
+
+/// 一个服务在一个批次内的日志，按产生顺序排列
+#[derive(Serialize, Clone, Debug)]
+pub struct LogBatch {
+    pub service_name: String,
+    pub lines: Vec<LogLine>,
+}
+
+#[derive(Default)]
+struct PendingLines {
+    lines: VecDeque<LogLine>,
+    truncated: usize,
+}
+
+/// 按服务合并日志行，定期整批取出，避免逐行发送事件压垮前端
+#[derive(Default)]
+pub struct LogBatcher {
+    pending: IndexMap<String, PendingLines>, // 按服务首次输出的顺序取出
+}
+
+impl LogBatcher {
+    pub fn push(&mut self, line: LogLine) {
+        let pending = self.pending.entry(line.service_name.clone()).or_default();
+        if pending.lines.len() >= MAX_LOG_BATCH_LINES {
+            pending.lines.pop_front();
+            pending.truncated += 1;
+        }
+        pending.lines.push_back(line);
+    }
+
+    /// 记录订阅者落后时被跳过的行数，以 SKIPPED_LOG_SERVICE 的截断标记行发送
+    pub fn push_skipped(&mut self, count: u64) {
+        self.push(LogLine::truncated(
+            SKIPPED_LOG_SERVICE,
+            count as usize,
+            unix_timestamp_ms(),
+        ));
+    }
+
+    /// 取出所有待发送的日志；发生截断的批次以 `[truncated N lines]` 标记行开头
+    pub fn drain(&mut self) -> Vec<LogBatch> {
+        self.pending
+            .drain(..)
+            .map(|(service_name, pending)| {
+                let mut lines = Vec::with_capacity(pending.lines.len() + 1);
+                if pending.truncated > 0 {
+                    let timestamp_ms = pending
+                        .lines
+                        .front()
+                        .map_or_else(unix_timestamp_ms, |line| line.timestamp_ms);
+                    lines.push(LogLine::truncated(
+                        &service_name,
+                        pending.truncated,
+                        timestamp_ms,
+                    ));
+                }
+                lines.extend(pending.lines);
+                LogBatch {
+                    service_name,
+                    lines,
+                }
+            })
+            .collect()
+    }
+}
+
+/// 解码控制台输出，非 UTF-8 内容按 GBK 处理，无法解码的字节替换为 U+FFFD
+pub fn decode_console_output(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => {
+            let (decoded, _, _) = GBK.decode(bytes);
+            decoded.to_string()
+        }
+    }
+}
+
+/// 在后台线程中逐行读取子进程输出并写入日志缓冲区，同时带服务名前缀输出到 xcontrol 控制台（终端中着色）
+pub fn capture_output<R: Read + Send + 'static>(
+    reader: R,
+    service_name: String,
+    stream: &'static str,
+    log_store: LogStore,
+) {
+    std::thread::spawn(move || {
+        let colored = if stream == "stderr" {
+            std::io::stderr().is_terminal()
+        } else {
+            std::io::stdout().is_terminal()
+        };
+        let reader = BufReader::new(reader);
+        for chunk in reader.split(b'\n') {
+            let bytes = match chunk {
+                Ok(bytes) => bytes,
+                Err(_) => break,
+            };
+            let line = decode_console_output(&bytes)
+                .trim_end_matches('\r')
+                .to_string();
+            let line = LogLine::new(&service_name, stream, line, unix_timestamp_ms());
+            if stream == "stderr" {
+                eprintln!("{}", line.console_text(colored));
+            } else {
+                println!("{}", line.console_text(colored));
+            }
+            log_store.push(line);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(service_name: &str, text: &str) -> LogLine {
+        LogLine::new(service_name, "stdout", text.to_string(), 0)
+    }
+
+    #[test]
+    fn lines_carry_prefix_and_stable_color() {
+        let api = line("api", "ready");
+        assert_eq!(api.prefix, "[api]");
+        assert_eq!(api.color, service_color("api"));
+        assert!(SERVICE_COLORS.contains(&api.color));
+        assert_eq!(api.console_text(false), "[api] ready");
+
+        let colored = api.console_text(true);
+        assert!(colored.starts_with("\x1b[3"));
+        assert!(colored.ends_with("[api]\x1b[0m ready"));
+    }
+
+    #[test]
+    fn utf8_output_is_kept() {
+        assert_eq!(decode_console_output("服务已启动".as_bytes()), "服务已启动");
+    }
+
+    #[test]
+    fn non_utf8_output_is_decoded_as_gbk() {
+        assert_eq!(decode_console_output(&[0xD6, 0xD0, 0xCE, 0xC4]), "中文");
+    }
+
+    #[test]
+    fn undecodable_output_is_replaced() {
+        assert_eq!(decode_console_output(b"ok \xFF"), "ok \u{FFFD}");
+    }
+
+    #[test]
+    fn batcher_groups_lines_by_service_in_arrival_order() {
+        let mut batcher = LogBatcher::default();
+        batcher.push(line("web", "1"));
+        batcher.push(line("api", "2"));
+        batcher.push(line("web", "3"));
+
+        let batches = batcher.drain();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].service_name, "web");
+        assert_eq!(batches[0].lines.len(), 2);
+        assert_eq!(batches[1].service_name, "api");
+        assert!(batcher.drain().is_empty());
+    }
+
+    #[test]
+    fn batcher_marks_truncated_lines() {
+        let mut batcher = LogBatcher::default();
+        for i in 0..MAX_LOG_BATCH_LINES + 5 {
+            batcher.push(line("api", &i.to_string()));
+        }
+
+        let batches = batcher.drain();
+        let lines = &batches[0].lines;
+        assert_eq!(lines.len(), MAX_LOG_BATCH_LINES + 1);
+        assert_eq!(lines[0].stream, "xcontrol");
+        assert_eq!(lines[0].line, "[truncated 5 lines]");
+        assert_eq!(lines[1].line, "5");
+    }
+
+    #[test]
+    fn batcher_marks_skipped_lines() {
+        let mut batcher = LogBatcher::default();
+        batcher.push(line("api", "before"));
+        batcher.push_skipped(42);
+
+        let batches = batcher.drain();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].service_name, SKIPPED_LOG_SERVICE);
+        assert_eq!(batches[1].lines[0].stream, "xcontrol");
+        assert_eq!(batches[1].lines[0].line, "[truncated 42 lines]");
+    }
+}
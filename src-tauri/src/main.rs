@@ -1,230 +1,371 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use serde::{Deserialize, Serialize};
+mod admin;
+mod cli;
+mod diagnostics;
+mod lock;
+mod persist;
+mod privilege;
+mod secrets;
+mod shell;
+
+use indexmap::IndexMap;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::process::Command;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::{
-    async_runtime,
-    AppHandle, Emitter, Manager, WebviewWindow, WindowEvent, Wry,
+    async_runtime, AppHandle, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+    WindowEvent, Wry,
 };
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::watch;
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+use tracing::level_filters::LevelFilter;
+use tracing::{debug, error, info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, Registry};
+
+use cli::{
+    CliOptions, EXIT_CONFIG_ERROR, EXIT_HEALTH_FAILURE, EXIT_OK, EXIT_SPAWN_FAILURE,
+    EXIT_STARTUP_TIMEOUT,
+};
+use lock::{ServiceLockStore, ServiceLocks};
+use persist::ServiceRecord;
+use privilege::{PrivilegeInfo, ProcessAccess};
+use xcontrol_lib::cleanup::{self, CleanupAction};
+use xcontrol_lib::config::{
+    self, build_health_check_url, check_working_dir, config_search_paths, dependency_start_order,
+    get_health_check_config, launch_tiers, load_services_config, service_args,
+    service_executable_path, service_window_mode, sort_by_dependencies, validate_detached,
+    validate_service, validate_services_config, DuplicateInstanceAction, HealthCheckConfig,
+    HealthCheckType, ServiceConfig, ServiceConfigOverrides, ServiceKind, ServicesConfig,
+    SplashConfig, DEFAULT_MAIN_WINDOW_LABEL,
+};
+use xcontrol_lib::drain::{self, DrainConfig};
+use xcontrol_lib::error::{is_transient_spawn_error, ServiceError};
+use xcontrol_lib::events::{
+    emit_service_event, ConfigCandidate, ConfigSearchData, ConfigWarningData, EventEmitter,
+    HeartbeatData, InstanceReplacingData, LogLevelChangedData, MonitoringData,
+    PrivilegeWarningData, RestartCircuitOpenData, RestartCircuitResetData, ServiceDrainingData,
+    ServiceEvent, ServiceEventData, ServiceProgressData, ServiceReadyData, ServiceRecheckedData,
+    ServiceRemovedData, ServiceStoppedData, StartupCompleteData, StartupTimeoutData, StopReason,
+    WaitingForNetworkData,
+};
+use xcontrol_lib::grpc_health::{self, ServingStatus};
+use xcontrol_lib::log_level::{self, LogLevel};
+use xcontrol_lib::logs::{self, decode_console_output, LogBatcher, LogBuffer, LogLine, LogStore};
+use xcontrol_lib::marker::ReadyFileConfig;
+use xcontrol_lib::metrics::{self, ServiceMetrics};
+use xcontrol_lib::network::{self, NetworkWaitConfig};
+use xcontrol_lib::precondition::Preconditions;
+use xcontrol_lib::priority::{self, ProcessPriority};
+use xcontrol_lib::process_backend::{self, BackendError, ProcessBackend, SpawnedChild};
+use xcontrol_lib::process_match::{self, ProcessMatch};
+use xcontrol_lib::restart::{self, RestartBackoff, RestartCircuit, RestartStats};
+use xcontrol_lib::signal::{self, StopSignal};
+use xcontrol_lib::state::{HealthSummary, ServiceState, StateChangedData};
+use xcontrol_lib::update::{self, UpdateInfo};
+use xcontrol_lib::{cert, health_export, job, launch, ready_log, tcp_probe, unix_timestamp_ms};
 
-#[cfg(windows)]
-use encoding_rs::GBK;
-#[cfg(windows)]
-use std::ffi::OsStr;
-#[cfg(windows)]
-use std::os::windows::ffi::OsStrExt;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 #[cfg(windows)]
-use std::ptr::null_mut;
-#[cfg(windows)]
 use winapi::um::handleapi::CloseHandle;
 #[cfg(windows)]
-use winapi::um::processthreadsapi::{OpenProcess, TerminateProcess};
+use winapi::um::processthreadsapi::{GetExitCodeProcess, OpenProcess, TerminateProcess};
 #[cfg(windows)]
 use winapi::um::winnt::{PROCESS_QUERY_INFORMATION, PROCESS_TERMINATE};
 #[cfg(windows)]
-use winapi::um::winuser::{GetClassInfoW, UnregisterClassW};
+use xcontrol_lib::window::{self, WindowMode};
 
-// --- 配置结构 ---
-#[derive(Deserialize, Clone)]
-struct ServiceConfig {
-    name: String,
-    executable: String,
-    working_dir: String,
-    #[serde(default)]
-    debug: bool, // 默认为 false
-    #[serde(default)]
-    args: Vec<String>, // 默认为空数组
-    #[serde(default)]
-    health_check: Option<HealthCheckConfig>, // 可选字段
-}
-
-#[derive(Deserialize, Clone)]
-struct HealthCheckConfig {
-    #[serde(default)]
-    enabled: bool, // 默认为 false
-    #[serde(default)]
-    url: String, // 默认为空字符串
-    #[serde(default)]
-    endpoint: String, // 默认为空字符串
-    #[serde(default = "default_max_retries")]
-    max_retries: usize,
-    #[serde(default = "default_retry_interval")]
-    retry_interval_ms: u64,
-}
-
-// 为 HealthCheckConfig 实现 Default trait
-impl Default for HealthCheckConfig {
-    fn default() -> Self {
-        Self {
-            enabled: false,
-            url: String::new(),
-            endpoint: String::new(),
-            max_retries: default_max_retries(),
-            retry_interval_ms: default_retry_interval(),
-        }
-    }
+/// 等待前端注册事件监听器的最长时间，超时后照常开始发送事件
+const FRONTEND_READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 前端就绪信号，由 frontend_ready 命令置位
+struct FrontendReady(watch::Sender<bool>);
+
+/// 按配置文件中的声明顺序保存，启动、停止、状态列表与报告均使用该顺序
+type ProcessManager = Arc<Mutex<IndexMap<String, ServiceInfo>>>;
+
+/// 最近一次健康检查的结果
+#[derive(Serialize, Clone, Copy)]
+struct HealthResult {
+    healthy: bool,
+    checked_at_ms: u64,
 }
 
-#[derive(Deserialize)]
-struct ServicesConfig {
-    services: Vec<ServiceConfig>,
+#[derive(Serialize, Clone)]
+struct ServiceInfo {
+    executable: String,              // 存储可执行文件名用于清理
+    state: ServiceState,             // 当前生命周期状态，只能通过 transition_service_state 修改
+    kill_timeout_ms: u64,            // 退出时等待正常退出的时间
+    restart_backoff: RestartBackoff, // 连续重启时的退避状态
+    stop_signal: StopSignal,         // 正常停止时发送的信号
+    process_match: ProcessMatch,     // 按进程名查找进程时的匹配方式
+    cleanup_patterns: Vec<String>,   // 停止时额外终止的辅助进程名模式
+    pid: Option<u32>,                // 由 xcontrol 启动或接管的主进程
+    monitoring_paused: bool,         // 维护模式下不检测进程意外退出
+    #[serde(skip)]
+    stdin: Option<Arc<Mutex<ChildStdin>>>, // 配置了 pipe_stdin 时服务进程的标准输入
+    group: Option<String>,           // 所属分组
+    restart_circuit: RestartCircuit, // 崩溃自动重启的熔断状态
+    launch_count: u64,               // 进程启动或被接管的次数
+    started_at_ms: Option<u64>,      // 当前主进程的启动或接管时间
+    last_health: Option<HealthResult>, // 最近一次健康检查的结果
+    restart_stats: RestartStats,     // 重新启动次数与时间，可由 reset_restart_stats 清零
+    persist_restart_stats: bool,     // 重启统计是否写入文件
+    idle_timeout_ms: Option<u64>,    // 空闲多久后自动停止
+    last_activity_ms: Option<u64>,   // 最近一次启动或 touch_service 的时间
+    detached: bool,                  // xcontrol 退出时保留该服务进程
 }
 
-fn default_max_retries() -> usize {
-    30
+/// 对外暴露的服务状态，前端命令与管理服务器共用
+#[derive(Serialize, Clone)]
+struct ServiceStatus {
+    service_name: String,
+    executable: String,
+    state: ServiceState,
+    group: Option<String>,
 }
-fn default_retry_interval() -> u64 {
-    1000
+
+/// 汇总所有已启动服务的当前状态
+fn collect_services_status(process_manager: &ProcessManager) -> Vec<ServiceStatus> {
+    let manager = process_manager.lock().unwrap();
+    manager
+        .iter()
+        .map(|(name, info)| ServiceStatus {
+            service_name: name.clone(),
+            executable: info.executable.clone(),
+            state: info.state,
+            group: info.group.clone(),
+        })
+        .collect()
 }
 
-// 全局进程管理器 - 现在只存储服务信息，不存储PID
-type ProcessManager = Arc<Mutex<HashMap<String, ServiceInfo>>>;
+/// 汇总所有服务的当前健康状态
+fn collect_health_summary(process_manager: &ProcessManager) -> HealthSummary {
+    let manager = process_manager.lock().unwrap();
+    HealthSummary::from_states(
+        manager
+            .iter()
+            .map(|(name, info)| (name.as_str(), info.state)),
+    )
+}
 
-#[derive(Clone)]
-struct ServiceInfo {
-    executable: String, // 存储可执行文件名用于清理
+/// 汇总所有服务的监控指标，读取进程内存时不持有锁
+fn collect_service_metrics(process_manager: &ProcessManager) -> Vec<ServiceMetrics> {
+    let services: Vec<_> = {
+        let manager = process_manager.lock().unwrap();
+        manager
+            .iter()
+            .map(|(name, info)| {
+                let pid = info.pid.filter(|_| info.state.has_process());
+                (name.clone(), info.state, info.restart_stats.count, pid)
+            })
+            .collect()
+    };
+    services
+        .into_iter()
+        .map(
+            |(service_name, state, restarts_total, pid)| ServiceMetrics {
+                service_name,
+                up: state == ServiceState::Healthy,
+                restarts_total,
+                memory_bytes: pid.and_then(metrics::resident_memory_bytes),
+            },
+        )
+        .collect()
 }
 
-/// 服务状态事件的数据结构
-#[derive(Serialize, Clone)]
-struct ServiceEventData {
-    service_name: String,
-    url: String,
-    error: String,
-    status: String, // "starting", "ready", "error"
-}
-
-/// 加载服务配置文件
-fn load_services_config() -> Result<ServicesConfig, Box<dyn std::error::Error + Send + Sync>> {
-    // 尝试多个可能的配置文件位置
-    let possible_paths = vec![
-        // 开发环境 - 项目根目录
-        PathBuf::from("services.dat"),
-        PathBuf::from("./services.dat"),
-        PathBuf::from("../services.dat"),
-        // 生产环境 - 可执行文件目录及其父目录
-        std::env::current_exe()?
-            .parent()
-            .unwrap()
-            .join("services.dat"),
-        std::env::current_exe()?
-            .parent()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .join("services.dat"),
-        // Tauri 应用目录
-        std::env::current_exe()?
-            .parent()
-            .unwrap()
-            .join("resources")
-            .join("services.dat"),
-    ];
+/// 登记配置中的服务；已登记的服务更新配置信息但保留当前状态
+fn register_service(
+    process_manager: &ProcessManager,
+    service: &ServiceConfig,
+    config: &ServicesConfig,
+) {
+    let kill_timeout_ms = service
+        .kill_timeout_ms
+        .unwrap_or(config.shutdown_timeout_ms);
+    let stop_signal = service.stop_signal.unwrap_or_default();
+    let persist_restart_stats = config.persist_restart_stats;
+    let saved_stats = persist_restart_stats
+        .then(|| persist::load_restart_stats(&service.name))
+        .flatten();
+    let mut manager = process_manager.lock().unwrap();
+    manager
+        .entry(service.name.clone())
+        .and_modify(|info| {
+            info.executable = service.executable.clone();
+            info.kill_timeout_ms = kill_timeout_ms;
+            info.stop_signal = stop_signal;
+            info.process_match = service.process_match;
+            info.cleanup_patterns = service.cleanup_patterns.clone();
+            info.group = service.group.clone();
+            info.persist_restart_stats = persist_restart_stats;
+            info.idle_timeout_ms = service.idle_timeout_ms;
+            info.detached = is_detached(service);
+        })
+        .or_insert_with(|| ServiceInfo {
+            executable: service.executable.clone(),
+            state: if service.lazy {
+                ServiceState::NotStarted
+            } else {
+                ServiceState::Stopped
+            },
+            kill_timeout_ms,
+            restart_backoff: RestartBackoff::default(),
+            stop_signal,
+            process_match: service.process_match,
+            cleanup_patterns: service.cleanup_patterns.clone(),
+            pid: None,
+            monitoring_paused: false,
+            stdin: None,
+            group: service.group.clone(),
+            restart_circuit: RestartCircuit::default(),
+            launch_count: 0,
+            started_at_ms: None,
+            last_health: None,
+            restart_stats: saved_stats.unwrap_or_default(),
+            persist_restart_stats,
+            idle_timeout_ms: service.idle_timeout_ms,
+            last_activity_ms: None,
+            detached: is_detached(service),
+        });
+}
 
-    println!("正在查找配置文件...");
+/// 以 --detach 启动时所有服务均独立运行
+static DETACH_ALL: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 
-    for path in &possible_paths {
-        println!("尝试路径: {:?}", path);
-        if path.exists() {
-            println!("找到配置文件: {:?}", path);
-            let config_content = std::fs::read_to_string(path)?;
-            let config: ServicesConfig = serde_json::from_str(&config_content)?;
-            println!("成功加载配置，包含 {} 个服务", config.services.len());
-            return Ok(config);
-        }
+/// 服务是否独立于 xcontrol 运行：配置了 detached 或以 --detach 启动
+fn is_detached(service: &ServiceConfig) -> bool {
+    service.detached || DETACH_ALL.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// 把服务进程树放入 Job Object；独立运行的服务不放入，避免随 xcontrol 的 Job 一起被终止
+fn attach_service_job(service: &ServiceConfig, pid: u32) {
+    if is_detached(service) {
+        info!("{} 服务独立运行，不放入 Job Object", service.name);
+        return;
     }
+    job::attach(&service.name, pid, service.limits.as_ref());
+}
 
-    // 如果找不到配置文件，输出当前工作目录和可执行文件路径用于调试
-    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("unknown"));
-    let exe_path = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("unknown"));
+/// 按配置顺序重排已登记的服务，配置中已删除的服务排在最后
+fn order_services(process_manager: &ProcessManager, services: &[ServiceConfig]) {
+    let position = |name: &str| {
+        services
+            .iter()
+            .position(|service| service.name == name)
+            .unwrap_or(usize::MAX)
+    };
+    let mut manager = process_manager.lock().unwrap();
+    manager.sort_by_cached_key(|name, _| position(name));
+}
 
-    let error_msg = format!(
-        "在所有可能的位置都找不到 services.dat 配置文件。\n当前工作目录: {:?}\n可执行文件路径: {:?}\n尝试的路径: {:#?}",
-        current_dir, exe_path, possible_paths
-    );
+/// 迁移服务状态，不合法的迁移会被拒绝；成功后发送 service_state_changed 事件
+fn transition_service_state(
+    emitter: &impl EventEmitter,
+    process_manager: &ProcessManager,
+    service_name: &str,
+    next: ServiceState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let from = {
+        let mut manager = process_manager.lock().unwrap();
+        let info = manager
+            .get_mut(service_name)
+            .ok_or_else(|| format!("未登记的服务: {}", service_name))?;
+        if !info.state.can_transition_to(next) {
+            return Err(format!(
+                "{} 服务无法从 {:?} 迁移到 {:?}",
+                service_name, info.state, next
+            )
+            .into());
+        }
+        std::mem::replace(&mut info.state, next)
+    };
 
-    println!("{}", error_msg);
-    Err(error_msg.into())
+    emit_service_event(
+        emitter,
+        ServiceEvent::StateChanged,
+        StateChangedData {
+            service_name: service_name.to_string(),
+            from,
+            to: next,
+        },
+    );
+    Ok(())
 }
 
-/// 获取指定进程名的所有进程PID
+/// 获取映像名与指定进程名匹配的所有进程PID
 fn get_processes_by_name(
     process_name: &str,
+    process_match: ProcessMatch,
+) -> Result<Vec<u32>, Box<dyn std::error::Error + Send + Sync>> {
+    let processes = list_processes(process_match.tasklist_filter(process_name))?;
+    Ok(processes
+        .into_iter()
+        .filter(|(image_name, _)| process_match.matches(image_name, process_name))
+        .map(|(_, pid)| pid)
+        .collect())
+}
+
+/// 查找映像名匹配通配符模式的进程，不包含 xcontrol 自身
+fn get_processes_by_pattern(
+    pattern: &str,
 ) -> Result<Vec<u32>, Box<dyn std::error::Error + Send + Sync>> {
-    let mut pids = Vec::new();
+    let own_pid = std::process::id();
+    let processes = list_processes(None)?;
+    Ok(processes
+        .into_iter()
+        .filter(|(image_name, pid)| {
+            *pid != own_pid && process_match::glob_matches(pattern, image_name)
+        })
+        .map(|(_, pid)| pid)
+        .collect())
+}
+
+/// 通过 tasklist 列出进程的映像名与 PID，filter 为 tasklist 的 /FI 过滤条件
+fn list_processes(
+    filter: Option<String>,
+) -> Result<Vec<(String, u32)>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut processes = Vec::new();
 
     let mut cmd = Command::new("tasklist");
-    cmd.args(&[
-        "/FI",
-        &format!("IMAGENAME eq {}", process_name),
-        "/FO",
-        "CSV",
-        "/NH",
-    ]);
+    if let Some(filter) = filter {
+        cmd.args(["/FI", &filter]);
+    }
+    cmd.args(["/FO", "CSV", "/NH"]);
 
     #[cfg(windows)]
-    {
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        cmd.creation_flags(CREATE_NO_WINDOW);
-    }
+    cmd.creation_flags(window::CREATE_NO_WINDOW);
 
     let output = cmd.output()?;
 
-    let output_str = if cfg!(windows) {
-        match String::from_utf8(output.stdout.clone()) {
-            Ok(s) => s,
-            Err(_) => {
-                let (decoded, _, _) = GBK.decode(&output.stdout);
-                decoded.to_string()
-            }
-        }
-    } else {
-        String::from_utf8(output.stdout)?
-    };
+    let output_str = decode_console_output(&output.stdout);
 
     for line in output_str.lines() {
-        if line.contains(process_name)
-            && !line.contains("找不到任务")
-            && !line.contains("INFO: No tasks")
-        {
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() >= 2 {
-                let pid_str = parts[1].trim_matches('"').trim();
-                if let Ok(pid) = pid_str.parse::<u32>() {
-                    pids.push(pid);
-                }
-            }
+        if line.contains("找不到任务") || line.contains("INFO: No tasks") {
+            continue;
         }
-    }
-
-    Ok(pids)
-}
-
-/// 检查并杀死指定名称的进程
-fn kill_existing_processes(
-    process_name: &str,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    println!("正在检查系统中是否存在 {} 进程...", process_name);
-
-    let pids = get_processes_by_name(process_name)?;
 
-    if pids.is_empty() {
-        println!("未发现运行中的 {} 进程", process_name);
-    } else {
-        for pid in pids {
-            println!("发现已存在的 {} 进程，PID: {}", process_name, pid);
-            kill_process_by_pid(pid);
+        // CSV 第一列为映像名，第二列为 PID；只比较映像名，避免误匹配其他列
+        let parts: Vec<&str> = line.split(',').collect();
+        if parts.len() >= 2 {
+            let image_name = parts[0].trim_matches('"').trim();
+            let pid_str = parts[1].trim_matches('"').trim();
+            if let Ok(pid) = pid_str.parse::<u32>() {
+                processes.push((image_name.to_string(), pid));
+            }
         }
     }
 
-    Ok(())
+    Ok(processes)
 }
 
 /// 通过 PID 杀死进程
@@ -233,15 +374,21 @@ fn kill_process_by_pid(pid: u32) {
     unsafe {
         let handle = OpenProcess(PROCESS_TERMINATE | PROCESS_QUERY_INFORMATION, 0, pid);
         if handle.is_null() {
-            eprintln!("无法打开进程 {} 进行终止", pid);
+            match privilege::last_open_process_error() {
+                ProcessAccess::AccessDenied => warn!(
+                    "无法终止进程 {}: 拒绝访问，该进程可能属于其他用户或以管理员权限运行",
+                    pid
+                ),
+                _ => warn!("无法终止进程 {}: 进程不存在或已退出", pid),
+            }
             return;
         }
 
         let result = TerminateProcess(handle, 1);
         if result != 0 {
-            println!("成功终止进程 {}", pid);
+            info!("成功终止进程 {}", pid);
         } else {
-            eprintln!("终止进程 {} 失败", pid);
+            error!("终止进程 {} 失败", pid);
         }
 
         CloseHandle(handle);
@@ -250,333 +397,4037 @@ fn kill_process_by_pid(pid: u32) {
 
 #[cfg(not(windows))]
 fn kill_process_by_pid(pid: u32) {
-    // 非Windows平台实现
-    let _ = Command::new("kill").arg("-9").arg(pid.to_string()).status();
+    if signal::send(pid, StopSignal::Kill) {
+        info!("成功终止进程 {}", pid);
+    }
 }
 
-/// 启动单个服务进程
-fn spawn_service_process(
-    service: &ServiceConfig,
-    process_manager: ProcessManager,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    println!("正在启动 {} 服务...", service.name);
+/// 基于系统命令与 API 的进程操作
+struct SystemProcesses;
 
-    // 清理已存在的同名进程
-    if let Err(e) = kill_existing_processes(&service.executable) {
-        eprintln!("清理已存在的 {} 进程时出错: {}", service.executable, e);
+impl ProcessBackend for SystemProcesses {
+    fn spawn(&self, command: &mut Command) -> std::io::Result<SpawnedChild> {
+        let child = command.spawn()?;
+        Ok(SpawnedChild {
+            pid: child.id(),
+            child: Some(child),
+        })
     }
 
-    // 等待进程完全终止
-    std::thread::sleep(Duration::from_millis(1000));
-
-    let exe_path: PathBuf = [&service.working_dir, &service.executable].iter().collect();
-    if !exe_path.exists() {
-        return Err(format!("{} 不存在于路径: {:?}", service.executable, exe_path).into());
+    fn kill_by_pid(&self, pid: u32) {
+        kill_process_by_pid(pid);
     }
 
-    let working_dir = PathBuf::from(&service.working_dir);
+    fn request_exit(&self, pid: u32, stop_signal: StopSignal) -> bool {
+        request_process_exit(pid, stop_signal)
+    }
 
-    let mut cmd = Command::new(&exe_path);
+    fn list_by_name(
+        &self,
+        process_name: &str,
+        process_match: ProcessMatch,
+    ) -> Result<Vec<u32>, BackendError> {
+        get_processes_by_name(process_name, process_match)
+    }
 
-    // 如果有参数才设置，避免设置空参数
-    if !service.args.is_empty() {
-        cmd.args(&service.args);
+    fn is_alive(&self, pid: u32) -> bool {
+        is_process_alive(pid)
     }
+}
 
-    cmd.current_dir(&working_dir);
+/// 服务启动、停止与清理使用的进程后端
+static PROCESSES: SystemProcesses = SystemProcesses;
 
-    #[cfg(windows)]
-    {
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        // 检查debug字段，默认为false（不显示窗口）
-        if !service.debug {
-            println!("{} 服务将以无窗口模式启动", service.name);
-            cmd.creation_flags(CREATE_NO_WINDOW);
-        } else {
-            println!("{} 服务将显示窗口启动", service.name);
+/// 请求进程正常退出，返回请求是否发送成功
+#[cfg(windows)]
+fn request_process_exit(pid: u32, _stop_signal: StopSignal) -> bool {
+    // 不带 /F 的 taskkill 会向进程窗口发送关闭消息；无窗口进程会直接失败
+    Command::new("taskkill")
+        .args(["/PID", &pid.to_string()])
+        .creation_flags(window::CREATE_NO_WINDOW)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+fn request_process_exit(pid: u32, stop_signal: StopSignal) -> bool {
+    signal::send(pid, stop_signal)
+}
+
+/// 检查进程是否仍在运行
+#[cfg(windows)]
+fn is_process_alive(pid: u32) -> bool {
+    const STILL_ACTIVE: u32 = 259;
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
         }
-    }
 
-    let child = cmd.spawn()?;
-    let pid = child.id();
+        let mut exit_code = 0;
+        let alive = GetExitCodeProcess(handle, &mut exit_code) != 0 && exit_code == STILL_ACTIVE;
+        CloseHandle(handle);
+        alive
+    }
+}
 
-    // 保存服务信息（不保存PID，因为可能会变化）
+#[cfg(not(windows))]
+fn is_process_alive(pid: u32) -> bool {
+    // 子进程未被回收时会以僵尸状态残留，`kill -0` 无法区分，因此读取进程状态
+    match Command::new("ps")
+        .args(["-o", "stat=", "-p", &pid.to_string()])
+        .output()
     {
-        let mut manager = process_manager.lock().unwrap();
-        manager.insert(
-            service.name.clone(),
-            ServiceInfo {
-                executable: service.executable.clone(),
-            },
-        );
+        Ok(output) => {
+            let stat = String::from_utf8_lossy(&output.stdout);
+            let stat = stat.trim();
+            !stat.is_empty() && !stat.starts_with('Z')
+        }
+        Err(_) => false,
     }
+}
 
-    println!("{} 服务进程已启动，PID: {}", service.name, pid);
-    Ok(())
+/// shell 模式下的完整命令行：executable 若是工作目录下的脚本文件则使用其完整路径，参数追加在后
+fn service_shell_command_line(service: &ServiceConfig, args: &[String]) -> String {
+    let script_path = service_executable_path(service);
+    let program = if script_path.is_file() {
+        shell::quote_arg(&script_path.to_string_lossy())
+    } else {
+        service.executable.clone()
+    };
+    shell::build_command_line(&program, args)
 }
 
-/// 获取服务的健康检查配置，如果没有配置则返回默认配置
-fn get_health_check_config(service: &ServiceConfig) -> HealthCheckConfig {
-    service.health_check.clone().unwrap_or_default()
+/// 返回 depends_on 中尚未成功完成的一次性任务
+fn unfinished_task_dependency(
+    process_manager: &ProcessManager,
+    config: &ServicesConfig,
+    service: &ServiceConfig,
+) -> Option<String> {
+    let manager = process_manager.lock().unwrap();
+    service
+        .depends_on
+        .iter()
+        .find(|dep| {
+            let is_task = config
+                .services
+                .iter()
+                .any(|s| &s.name == *dep && s.kind == ServiceKind::Oneshot);
+            is_task
+                && manager
+                    .get(dep.as_str())
+                    .is_none_or(|info| info.state != ServiceState::Completed)
+        })
+        .cloned()
 }
 
-/// 健康检查
-async fn check_service_health(service: &ServiceConfig) -> bool {
-    let health_check = get_health_check_config(service);
+/// 返回 depends_on 中已启动但未就绪（健康检查失败或进程已退出）的服务
+fn unready_service_dependency(
+    process_manager: &ProcessManager,
+    service: &ServiceConfig,
+) -> Option<String> {
+    let manager = process_manager.lock().unwrap();
+    service
+        .depends_on
+        .iter()
+        .find(|dep| {
+            manager
+                .get(dep.as_str())
+                .is_some_and(|info| needs_recovery(info.state))
+        })
+        .cloned()
+}
 
-    if !health_check.enabled {
-        println!("{} 服务未启用健康检查，跳过", service.name);
-        return true; // 不需要健康检查的服务直接返回成功
+/// 校验错误对应的退出码
+fn validation_exit_code(error: &ServiceError) -> i32 {
+    if error.is_config_error() {
+        EXIT_CONFIG_ERROR
+    } else {
+        EXIT_SPAWN_FAILURE
     }
+}
 
-    if health_check.url.is_empty() {
-        println!("{} 服务健康检查URL为空，跳过检查", service.name);
-        return true;
+/// 最小化启动时交给 CreateProcessW 的完整命令行
+#[cfg(windows)]
+fn minimized_command_line(service: &ServiceConfig, args: &[String]) -> String {
+    if service.shell {
+        format!(
+            "cmd {}",
+            shell::cmd_arguments(&service_shell_command_line(service, args))
+        )
+    } else {
+        let exe_path = service_executable_path(service);
+        shell::build_command_line(&shell::quote_arg(&exe_path.to_string_lossy()), args)
     }
+}
 
-    let client = reqwest::Client::new();
-    let health_check_url = format!("{}{}", health_check.url, health_check.endpoint);
+/// 新启动的服务进程
+struct SpawnedProcess {
+    pid: u32,
+    stdin: Option<ChildStdin>, // 仅配置了 pipe_stdin 的无窗口服务
+    child: Option<Child>,      // 用于等待一次性任务退出；最小化窗口启动时为 None
+}
 
-    println!(
-        "开始对 {} 服务进行健康检查，URL: {}",
-        service.name, health_check_url
-    );
+/// 两次重试创建进程之间的等待时间
+const SPAWN_RETRY_INTERVAL: Duration = Duration::from_millis(500);
 
-    for attempt in 1..=health_check.max_retries {
-        match client.get(&health_check_url).send().await {
-            Ok(response) if response.status().is_success() => {
-                println!(
-                    "{} 服务已就绪！（尝试 {} / {}）",
-                    service.name, attempt, health_check.max_retries
-                );
-                return true;
-            }
-            Ok(response) => {
-                println!(
-                    "{} 服务未就绪，状态码: {}（尝试 {} / {}）",
-                    service.name,
-                    response.status(),
-                    attempt,
-                    health_check.max_retries
-                );
-            }
-            Err(e) => {
-                println!(
-                    "{} 无法连接到服务: {}（尝试 {} / {}）",
-                    service.name, e, attempt, health_check.max_retries
+/// 创建进程，可执行文件暂时被占用时按 spawn_retries 重试
+fn spawn_with_retry<T>(
+    service: &ServiceConfig,
+    mut spawn: impl FnMut() -> std::io::Result<T>,
+) -> std::io::Result<T> {
+    let mut retries = 0;
+    loop {
+        match spawn() {
+            Err(e) if retries < service.spawn_retries && is_transient_spawn_error(&e) => {
+                retries += 1;
+                warn!(
+                    "{} 服务的可执行文件可能仍被占用: {}，{:?} 后重试（{} / {}）",
+                    service.name, e, SPAWN_RETRY_INTERVAL, retries, service.spawn_retries
                 );
+                std::thread::sleep(SPAWN_RETRY_INTERVAL);
             }
+            result => return result,
         }
-        sleep(Duration::from_millis(health_check.retry_interval_ms)).await;
     }
-
-    println!("{} 服务健康检查失败，已达到最大重试次数", service.name);
-    false
 }
 
-/// 启动所有服务并通知前端
-async fn start_all_services_and_notify(window: WebviewWindow, process_manager: ProcessManager) {
-    let config = match load_services_config() {
-        Ok(config) => config,
-        Err(e) => {
-            eprintln!("加载配置文件失败: {}", e);
-            let event_data = ServiceEventData {
-                service_name: "config".to_string(),
-                url: String::new(),
-                error: format!("加载配置文件失败: {}", e),
-                status: "error".to_string(),
-            };
-            let _ = window.emit("service_error", event_data);
-            return;
+/// 启动单个服务进程，返回进程 PID
+fn spawn_service_process(
+    service: &ServiceConfig,
+    log_store: LogStore,
+) -> Result<SpawnedProcess, Box<dyn std::error::Error + Send + Sync>> {
+    info!("正在启动 {} 服务...", service.name);
+
+    // 清理已存在的同名进程
+    if service.kill_existing {
+        if let Err(e) =
+            process_backend::kill_existing(&PROCESSES, &service.executable, service.process_match)
+        {
+            warn!("清理已存在的 {} 进程时出错: {}", service.executable, e);
         }
-    };
+    } else {
+        info!(
+            "{} 服务未启用 kill_existing，跳过同名进程检查",
+            service.name
+        );
+    }
 
-    println!("开始启动 {} 个服务", config.services.len());
+    let working_dir = PathBuf::from(&service.working_dir);
+    check_working_dir(&working_dir)?;
 
-    for service in &config.services {
-        println!("处理服务: {}", service.name);
-        println!("  - 可执行文件: {}", service.executable);
-        println!("  - 工作目录: {}", service.working_dir);
-        println!("  - 调试模式: {}", service.debug);
-        println!("  - 参数: {:?}", service.args);
+    cleanup::run_all(
+        &service.cleanup,
+        &working_dir,
+        &format!("{} 服务启动前清理", service.name),
+    );
 
-        // 打印健康检查配置
-        let health_check = get_health_check_config(service);
-        println!(
-            "  - 健康检查: enabled={}, url={}",
-            health_check.enabled, health_check.url
-        );
+    if let Some(ready_file) = service_ready_file(service).filter(|file| file.remove_stale) {
+        ready_file.remove_stale(&working_dir);
+    }
 
-        // 通知前端服务正在启动
-        let event_data = ServiceEventData {
-            service_name: service.name.clone(),
-            url: String::new(),
-            error: String::new(),
-            status: "starting".to_string(),
-        };
-        let _ = window.emit("service_starting", event_data);
-
-        // 启动服务进程
-        match spawn_service_process(service, process_manager.clone()) {
-            Ok(_) => {
-                // 等待一小段时间让进程完全启动
-                sleep(Duration::from_millis(2000)).await;
-
-                // 进行健康检查
-                if check_service_health(service).await {
-                    let health_check = get_health_check_config(service);
-                    let event_data = ServiceEventData {
-                        service_name: service.name.clone(),
-                        url: health_check.url.clone(),
-                        error: String::new(),
-                        status: "ready".to_string(),
-                    };
-                    let _ = window.emit("service_ready", event_data);
-                } else {
-                    let event_data = ServiceEventData {
-                        service_name: service.name.clone(),
-                        url: String::new(),
-                        error: "服务启动超时或健康检查失败".to_string(),
-                        status: "error".to_string(),
-                    };
-                    let _ = window.emit("service_error", event_data);
-                }
-            }
-            Err(e) => {
-                eprintln!("启动 {} 服务失败: {}", service.name, e);
-                let event_data = ServiceEventData {
-                    service_name: service.name.clone(),
-                    url: String::new(),
-                    error: format!("启动服务失败: {}", e),
-                    status: "error".to_string(),
-                };
-                let _ = window.emit("service_error", event_data);
+    // 参数与环境变量中的 secret:// 引用在此解析，日志中只打印原始引用
+    let configured_args = service_args(service);
+    let args = secrets::resolve_all(&configured_args)?;
+    let env = secrets::resolve_env(&service.env)?;
+
+    let mut cmd = if service.shell {
+        info!(
+            "{} 服务将通过 shell 启动: {}",
+            service.name,
+            service_shell_command_line(service, &configured_args)
+        );
+        shell::shell_command(&service_shell_command_line(service, &args))
+    } else {
+        let exe_path = service_executable_path(service);
+        if !exe_path.exists() {
+            return Err(ServiceError::ExecutableMissing {
+                executable: service.executable.clone(),
+                path: exe_path,
             }
+            .into());
         }
-    }
-}
 
-/// 应用退出时的清理函数 - 修改为使用进程名而不是PID
-fn cleanup_on_exit(process_manager: ProcessManager) {
-    println!("应用正在退出，执行清理操作...");
+        let mut cmd = Command::new(&exe_path);
 
-    // 使用作用域锁，避免长时间持有锁
-    let services: Vec<(String, String)> = {
-        let manager = process_manager.lock().unwrap();
-        manager
-            .iter()
-            .map(|(name, info)| (name.clone(), info.executable.clone()))
-            .collect()
+        // 如果有参数才设置，避免设置空参数
+        if !args.is_empty() {
+            cmd.args(&args);
+        }
+        cmd
     };
 
-    for (service_name, executable) in services {
-        println!("正在查找并终止 {} 服务的所有进程...", service_name);
+    cmd.current_dir(&working_dir);
+    cmd.envs(&env);
 
-        // 使用进程名查找所有相关进程并终止
-        match get_processes_by_name(&executable) {
-            Ok(pids) => {
-                if pids.is_empty() {
-                    println!("未找到 {} 服务的运行进程", service_name);
-                } else {
-                    for pid in pids {
-                        println!("正在终止 {} 服务进程 (PID: {})...", service_name, pid);
-                        kill_process_by_pid(pid);
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("查找 {} 服务进程时出错: {}", service_name, e);
-            }
+    #[cfg(unix)]
+    if let Some(mask) = service
+        .umask
+        .as_deref()
+        .and_then(xcontrol_lib::config::parse_umask)
+    {
+        use std::os::unix::process::CommandExt;
+        let mask = mask as libc::mode_t;
+        // umask 是异步信号安全的，可以在 fork 之后、exec 之前调用，只影响子进程
+        unsafe {
+            cmd.pre_exec(move || {
+                libc::umask(mask);
+                Ok(())
+            });
         }
     }
 
-    // 等待进程完全终止
-    std::thread::sleep(Duration::from_millis(1000));
-    println!("清理操作完成");
-}
+    let window_mode = service_window_mode(service);
+    info!("{} 服务窗口模式: {:?}", service.name, window_mode);
 
-/// 释放Windows窗口类资源
-#[cfg(windows)]
-unsafe fn cleanup_window_classes() {
-    // 转换类名为宽字符
-    let class_name = OsStr::new("Chrome_WidgetWin_0")
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect::<Vec<_>>();
-
-    let mut wnd_class = std::mem::zeroed();
-    // 检查类是否存在
-    if GetClassInfoW(null_mut(), class_name.as_ptr(), &mut wnd_class) != 0 {
-        // 注销窗口类
-        if UnregisterClassW(class_name.as_ptr(), null_mut()) == 0 {
-            let err = winapi::um::errhandlingapi::GetLastError();
-            if err != 1412 {
-                // 忽略"类仍有打开的窗口"错误
-                eprintln!("注销窗口类失败，错误码: {}", err);
-            }
-        } else {
-            println!("成功注销 Chrome_WidgetWin_0 窗口类");
-        }
+    // 最小化窗口需要设置 STARTUPINFO，std 的 Command 不支持，改用 CreateProcessW 启动
+    // 一次性任务需要 Child 读取退出码，不使用最小化窗口
+    #[cfg(windows)]
+    if window_mode == WindowMode::Minimized && service.kind == ServiceKind::Service {
+        let command_line = minimized_command_line(service, &args);
+        let pid = spawn_with_retry(service, || {
+            window::spawn_minimized(&command_line, &working_dir, &env)
+        })?;
+        info!("{} 服务进程已启动，PID: {}", service.name, pid);
+        apply_service_priority(service, pid);
+        return Ok(SpawnedProcess {
+            pid,
+            stdin: None,
+            child: None,
+        });
     }
-}
 
-/// 聚焦并显示现有窗口
-fn focus_existing_window(app_handle: &AppHandle<Wry>) {
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.show();
-        let _ = window.set_focus();
-        let _ = window.unminimize();
-        println!("已聚焦到现有的应用窗口");
+    // 无窗口模式下捕获输出写入日志缓冲区；有窗口时保留在服务自己的控制台窗口中
+    let mut merged_output = None;
+    if is_detached(service) {
+        // xcontrol 退出后管道会关闭，继续写输出的服务可能因此退出
+        warn!(
+            "{} 服务独立运行，不捕获输出，日志中不会出现其输出",
+            service.name
+        );
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+    } else if window_mode.captures_output() {
+        let stdin = if service.pipe_stdin {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        };
+        cmd.stdin(stdin);
+        if service.stderr_to_stdout {
+            // 两个流共用同一个管道，读取顺序即进程写出的顺序
+            let (reader, writer) = std::io::pipe()?;
+            cmd.stdout(writer.try_clone()?).stderr(writer);
+            merged_output = Some(reader);
+        } else {
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
     }
-}
 
-/// 安全退出应用
-fn safe_exit(app_handle: AppHandle<Wry>, process_manager: ProcessManager) {
-    // 1. 立即隐藏窗口
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.hide();
+    #[cfg(windows)]
+    if window_mode == WindowMode::Hidden {
+        cmd.creation_flags(window::CREATE_NO_WINDOW);
     }
 
-    // 2. 在后台线程执行清理
-    std::thread::spawn(move || {
-        println!("开始后台清理...");
-
-        // 执行进程清理
-        cleanup_on_exit(process_manager);
+    let SpawnedChild { pid, child } = spawn_with_retry(service, || PROCESSES.spawn(&mut cmd))?;
+    let mut child = child.ok_or("进程后端未返回子进程")?;
+    // Command 持有管道写端，需先释放，进程退出后读取端才能读到结尾
+    drop(cmd);
 
-        // Windows平台额外清理窗口类
-        #[cfg(windows)]
-        unsafe {
-            cleanup_window_classes();
-        }
+    if let Some(output) = merged_output {
+        logs::capture_output(output, service.name.clone(), "stdout", log_store.clone());
+    }
+    if let Some(stdout) = child.stdout.take() {
+        logs::capture_output(stdout, service.name.clone(), "stdout", log_store.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        logs::capture_output(stderr, service.name.clone(), "stderr", log_store);
+    }
 
-        println!("清理完成，正在退出应用...");
+    info!("{} 服务进程已启动，PID: {}", service.name, pid);
+    apply_service_priority(service, pid);
+    Ok(SpawnedProcess {
+        pid,
+        stdin: child.stdin.take(),
+        child: Some(child),
+    })
+}
 
-        // 延迟退出，确保资源完全释放
-        std::thread::sleep(Duration::from_millis(500));
+/// 上次启动且仍在运行的服务进程记录；PID 可能已被系统复用，需同时核对进程名
+fn adoptable_process(service: &ServiceConfig) -> Option<ServiceRecord> {
+    // shell 模式下记录的是 shell 进程，无法按 executable 核对；一次性任务每次都重新运行；
+    // 无法读取被接管进程的输出，就绪日志检查的服务同样重新启动
+    if service.shell
+        || service.kind == ServiceKind::Oneshot
+        || get_health_check_config(service).check_type == HealthCheckType::Log
+    {
+        return None;
+    }
+    let record = persist::load_record(&service.name)?;
+    if !is_process_alive(record.pid) {
+        return None;
+    }
+    get_processes_by_name(&service.executable, service.process_match)
+        .is_ok_and(|pids| pids.contains(&record.pid))
+        .then_some(record)
+}
 
-        // 强制退出（避免Tauri的清理钩子冲突）
-        std::process::exit(0);
-    });
+/// 构造单次健康检查请求
+fn health_check_request(
+    client: &reqwest::Client,
+    url: &reqwest::Url,
+    auth_bearer: Option<&str>,
+    timeout_ms: Option<u64>,
+) -> reqwest::RequestBuilder {
+    let mut request = client.get(url.clone());
+    if let Some(timeout_ms) = timeout_ms {
+        request = request.timeout(Duration::from_millis(timeout_ms));
+    }
+    match auth_bearer {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
 }
 
-fn main() {
-    // 创建进程管理器
-    let process_manager: ProcessManager = Arc::new(Mutex::new(HashMap::new()));
-    let cleanup_manager = process_manager.clone();
+/// 按健康检查的重试次数与间隔反复调用 probe，直到返回 Ok；probe 返回 Err 时记录未就绪原因后重试。
+/// 每次探测与重试等待都可被取消令牌中止，中止或达到最大重试次数时返回 None
+async fn retry_probe<T, F, Fut>(
+    service: &ServiceConfig,
+    health_check: &HealthCheckConfig,
+    cancel: &CancellationToken,
+    mut probe: F,
+) -> Option<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, String>>,
+{
+    let attempts = health_check.attempts();
+    for attempt in 1..=attempts {
+        let result = tokio::select! {
+            result = probe() => result,
+            _ = cancel.cancelled() => {
+                info!("{} 服务健康检查已中止", service.name);
+                return None;
+            }
+        };
+        match result {
+            Ok(value) => {
+                info!(
+                    "{} 服务已就绪！（尝试 {} / {}）",
+                    service.name, attempt, attempts
+                );
+                return Some(value);
+            }
+            Err(reason) => info!(
+                "{} {}（尝试 {} / {}）",
+                service.name, reason, attempt, attempts
+            ),
+        }
+        if attempt == attempts {
+            break;
+        }
+        tokio::select! {
+            _ = sleep(Duration::from_millis(health_check.retry_interval_ms)) => {}
+            _ = cancel.cancelled() => {
+                info!("{} 服务健康检查已中止", service.name);
+                return None;
+            }
+        }
+    }
+
+    error!("{} 服务健康检查失败，已达到最大重试次数", service.name);
+    None
+}
+
+/// 解析健康检查 Bearer 令牌中的 secret:// 引用，读取失败时记录原因，不再重试
+fn resolve_auth_bearer(
+    service: &ServiceConfig,
+    health_check: &HealthCheckConfig,
+) -> Result<Option<String>, HealthFailure> {
+    health_check
+        .auth_bearer
+        .as_deref()
+        .map(secrets::resolve)
+        .transpose()
+        .map_err(|e| {
+            warn!("{} 服务健康检查{}", service.name, e);
+            HealthFailure::Unhealthy
+        })
+}
+
+/// 服务启用的就绪标记文件检查
+fn service_ready_file(service: &ServiceConfig) -> Option<ReadyFileConfig> {
+    let health_check = get_health_check_config(service);
+    if health_check.enabled && health_check.check_type == HealthCheckType::File {
+        health_check.file
+    } else {
+        None
+    }
+}
+
+/// 在重试次数内轮询就绪标记文件，取消令牌触发时立即中止并返回失败
+async fn check_ready_file(
+    service: &ServiceConfig,
+    health_check: &HealthCheckConfig,
+    cancel: &CancellationToken,
+) -> bool {
+    let Some(ready_file) = &health_check.file else {
+        warn!("{} 服务未配置就绪标记文件", service.name);
+        return false;
+    };
+    let working_dir = Path::new(&service.working_dir);
+    info!(
+        "开始检查 {} 服务的就绪标记文件: {:?}",
+        service.name,
+        ready_file.resolve(working_dir)
+    );
+
+    retry_probe(service, health_check, cancel, || async {
+        if ready_file.is_ready(working_dir) {
+            Ok(())
+        } else {
+            Err("就绪标记文件尚未就绪".to_string())
+        }
+    })
+    .await
+    .is_some()
+}
+
+/// 从未就绪的健康检查响应中读取启动进度并发送 service_progress 事件
+async fn report_startup_progress(
+    emitter: &impl EventEmitter,
+    service: &ServiceConfig,
+    pointer: &str,
+    response: reqwest::Response,
+) {
+    let Ok(body) = response.bytes().await else {
+        return;
+    };
+    if let Some(progress) = health_export::read_progress(&body, pointer) {
+        emit_service_event(
+            emitter,
+            ServiceEvent::Progress,
+            ServiceProgressData {
+                service_name: service.name.clone(),
+                progress,
+            },
+        );
+    }
+}
+
+/// 按 export 配置从健康检查响应中提取字段，未配置时返回 null；读取响应体失败时 json 字段为 null
+async fn export_health_details(
+    health_check: &HealthCheckConfig,
+    response: reqwest::Response,
+) -> serde_json::Value {
+    let Some(export) = &health_check.export else {
+        return serde_json::Value::Null;
+    };
+    let headers = response.headers().clone();
+    let body = if export.needs_body() {
+        response.bytes().await.unwrap_or_default()
+    } else {
+        Default::default()
+    };
+    export.extract(&headers, &body)
+}
+
+/// 在重试次数内尝试连接 TCP 地址，取消令牌触发时立即中止并返回失败
+async fn check_tcp_address(
+    service: &ServiceConfig,
+    health_check: &HealthCheckConfig,
+    cancel: &CancellationToken,
+) -> bool {
+    let Some(address) = &health_check.address else {
+        warn!("{} 服务未配置 TCP 健康检查地址", service.name);
+        return false;
+    };
+    info!("开始对 {} 服务进行 TCP 健康检查: {}", service.name, address);
+
+    let connected = retry_probe(service, health_check, cancel, || async {
+        tcp_probe::probe(address, tcp_probe::CONNECT_TIMEOUT)
+            .await
+            .map_err(|e| format!("无法连接到服务: {}", e))
+    })
+    .await;
+    if let Some(addr) = connected {
+        info!("{} 服务已连接 {}", service.name, addr);
+    }
+    connected.is_some()
+}
+
+/// 在 max_retries × retry_interval_ms 内等待服务输出匹配 ready_pattern 的行，
+/// 只接受进程启动之后的输出；进程先退出或取消令牌触发时返回失败
+async fn check_ready_log(
+    service: &ServiceConfig,
+    health_check: &HealthCheckConfig,
+    log_store: &LogStore,
+    process: &ServiceRecord,
+    cancel: &CancellationToken,
+) -> bool {
+    let pattern = health_check.ready_pattern.as_deref().unwrap_or_default();
+    let regex = match ready_log::compile(pattern) {
+        Ok(regex) => regex,
+        Err(e) => {
+            warn!("{} 服务就绪日志模式无效: {}", service.name, e);
+            return false;
+        }
+    };
+    info!("开始等待 {} 服务输出就绪日志: {}", service.name, pattern);
+
+    let is_ready = |line: &LogLine| {
+        ready_log::is_ready_line(&regex, line, &service.name, process.started_at_ms)
+    };
+    let buffered_ready = || {
+        log_store
+            .recent(&service.name)
+            .is_some_and(|lines| lines.iter().any(is_ready))
+    };
+    // 先订阅再读取缓存，避免漏掉两者之间输出的行
+    let mut receiver = log_store.subscribe();
+    if buffered_ready() {
+        info!("{} 服务已就绪，已输出就绪日志", service.name);
+        return true;
+    }
+
+    let timeout = health_check.retry_interval_ms * health_check.attempts() as u64;
+    let deadline = sleep(Duration::from_millis(timeout));
+    tokio::pin!(deadline);
+    let mut exit_check = tokio::time::interval(PROCESS_WATCH_INTERVAL);
+    loop {
+        tokio::select! {
+            line = receiver.recv() => {
+                let ready = match line {
+                    Ok(line) => is_ready(&line),
+                    // 积压的行已写入缓存，重新读取缓存
+                    Err(RecvError::Lagged(_)) => buffered_ready(),
+                    Err(RecvError::Closed) => return false,
+                };
+                if ready {
+                    info!("{} 服务已就绪，已输出就绪日志", service.name);
+                    return true;
+                }
+            }
+            _ = exit_check.tick() => {
+                if !is_process_alive(process.pid) {
+                    warn!("{} 服务进程在输出就绪日志前已退出", service.name);
+                    return false;
+                }
+            }
+            _ = &mut deadline => {
+                warn!("{} 服务在 {}ms 内未输出就绪日志", service.name, timeout);
+                return false;
+            }
+            _ = cancel.cancelled() => {
+                info!("{} 服务健康检查已中止", service.name);
+                return false;
+            }
+        }
+    }
+}
+
+/// 按 gRPC 健康检查协议检查服务，返回 SERVING 视为就绪，其他状态与调用失败均重试
+async fn check_grpc_health(
+    service: &ServiceConfig,
+    health_check: &HealthCheckConfig,
+    cancel: &CancellationToken,
+) -> bool {
+    let url = match build_health_check_url(&health_check.url, "") {
+        Ok(url) => url,
+        Err(e) => {
+            warn!("{} 服务 gRPC 健康检查地址无效: {}", service.name, e);
+            return false;
+        }
+    };
+    let grpc_service = health_check.grpc_service.as_deref().unwrap_or_default();
+    let Ok(auth_bearer) = resolve_auth_bearer(service, health_check) else {
+        return false;
+    };
+    let channel = match grpc_health::channel(
+        url.as_str(),
+        health_check.connect_timeout_ms.map(Duration::from_millis),
+        health_check.request_timeout_ms.map(Duration::from_millis),
+    ) {
+        Ok(channel) => channel,
+        Err(e) => {
+            error!("{} 服务健康检查客户端创建失败: {}", service.name, e);
+            return false;
+        }
+    };
+    info!(
+        "开始对 {} 服务进行 gRPC 健康检查: {} (service: {:?})",
+        service.name, url, grpc_service
+    );
+
+    retry_probe(service, health_check, cancel, || async {
+        let status = grpc_health::check(channel.clone(), grpc_service, auth_bearer.as_deref())
+            .await
+            .map_err(|e| format!("gRPC 健康检查失败: {}", e))?;
+        match status {
+            ServingStatus::Serving => Ok(()),
+            status => Err(format!("服务未就绪，gRPC 健康状态: {:?}", status)),
+        }
+    })
+    .await
+    .is_some()
+}
+
+/// 健康检查客户端的缓存键：重定向、TLS、连接超时与代理选项
+/// （follow_redirects, accept_invalid_certs, ca_cert_path, connect_timeout_ms, proxy）
+type HealthClientKey = (bool, bool, Option<String>, Option<u64>, Option<String>);
+
+/// 选项相同的服务共用同一个客户端及其连接池，客户端内部为引用计数，可在并行的健康检查间共享
+static HEALTH_CLIENTS: Mutex<Option<HashMap<HealthClientKey, reqwest::Client>>> = Mutex::new(None);
+
+/// 获取与健康检查配置对应的共享 HTTP 客户端，首次使用时创建
+fn health_check_client(health_check: &HealthCheckConfig) -> Result<reqwest::Client, String> {
+    let key: HealthClientKey = (
+        health_check.follow_redirects,
+        health_check.accept_invalid_certs,
+        health_check.ca_cert_path.clone(),
+        health_check.connect_timeout_ms,
+        health_check.proxy_url(),
+    );
+    if let Some(client) = HEALTH_CLIENTS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|clients| clients.get(&key))
+    {
+        return Ok(client.clone());
+    }
+
+    // 创建时可能读取证书文件，不持有锁
+    let client = build_health_check_client(health_check)?;
+    let mut clients = HEALTH_CLIENTS.lock().unwrap();
+    Ok(clients
+        .get_or_insert_with(HashMap::new)
+        .entry(key)
+        .or_insert(client)
+        .clone())
+}
+
+/// 按健康检查配置的重定向、TLS、连接超时与代理选项创建 HTTP 客户端
+fn build_health_check_client(health_check: &HealthCheckConfig) -> Result<reqwest::Client, String> {
+    // 不跟随重定向时 3xx 响应不是成功状态码，按未就绪处理
+    let redirect_policy = if health_check.follow_redirects {
+        reqwest::redirect::Policy::default()
+    } else {
+        reqwest::redirect::Policy::none()
+    };
+    // 保留服务器证书，供 min_cert_days_remaining 检查读取
+    let mut builder = reqwest::Client::builder()
+        .redirect(redirect_policy)
+        .tls_info(true)
+        .danger_accept_invalid_certs(health_check.accept_invalid_certs);
+    if let Some(connect_timeout_ms) = health_check.connect_timeout_ms {
+        builder = builder.connect_timeout(Duration::from_millis(connect_timeout_ms));
+    }
+    // 未配置代理时保持 reqwest 的默认行为，读取系统代理环境变量
+    if let Some(proxy) = health_check.proxy_url() {
+        let proxy =
+            reqwest::Proxy::all(&proxy).map_err(|e| format!("无效的代理地址 {}: {}", proxy, e))?;
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(path) = &health_check.ca_cert_path {
+        let content =
+            std::fs::read(path).map_err(|e| format!("读取根证书 {} 失败: {}", path, e))?;
+        let certificate = reqwest::Certificate::from_pem(&content)
+            .or_else(|_| reqwest::Certificate::from_der(&content))
+            .map_err(|e| format!("解析根证书 {} 失败: {}", path, e))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// 记录最近一次健康检查的结果，供 dump_state 输出
+fn record_health_result(process_manager: &ProcessManager, name: &str, healthy: bool) {
+    if let Some(info) = process_manager.lock().unwrap().get_mut(name) {
+        info.last_health = Some(HealthResult {
+            healthy,
+            checked_at_ms: unix_timestamp_ms(),
+        });
+    }
+}
+
+/// 健康检查失败的原因
+enum HealthFailure {
+    /// 重试次数内未就绪、配置无效或已取消
+    Unhealthy,
+    /// 服务可用但 HTTPS 证书在 min_cert_days_remaining 天内过期，附带剩余天数
+    CertExpiring(i64),
+}
+
+/// 检查 HTTPS 健康检查响应的服务器证书剩余有效期；无法读取证书时只记录，不判定失败
+fn check_cert_expiry(
+    service: &ServiceConfig,
+    url: &reqwest::Url,
+    response: &reqwest::Response,
+    min_days: u32,
+) -> Result<(), HealthFailure> {
+    if url.scheme() != "https" {
+        return Ok(());
+    }
+    let now_secs = (unix_timestamp_ms() / 1000) as i64;
+    let days = response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .and_then(|der| cert::days_remaining(der, now_secs));
+    match days {
+        Some(days) if days < i64::from(min_days) => {
+            warn!(
+                "{} 服务的证书将在 {} 天后过期，低于 min_cert_days_remaining ({})",
+                service.name, days, min_days
+            );
+            Err(HealthFailure::CertExpiring(days))
+        }
+        Some(days) => {
+            info!("{} 服务的证书剩余 {} 天有效期", service.name, days);
+            Ok(())
+        }
+        None => {
+            warn!("无法读取 {} 服务的证书有效期，跳过证书检查", service.name);
+            Ok(())
+        }
+    }
+}
+
+/// 健康检查，取消令牌触发时立即中止并返回失败；process 为被检查的服务进程，用于就绪日志检查
+async fn check_service_health(
+    emitter: &impl EventEmitter,
+    log_store: &LogStore,
+    service: &ServiceConfig,
+    process: &ServiceRecord,
+    cancel: &CancellationToken,
+) -> Result<serde_json::Value, HealthFailure> {
+    let health_check = get_health_check_config(service);
+
+    if !health_check.enabled {
+        // 只能确认进程在启动等待后仍存活，无法得知服务是否真正可用
+        if !is_process_alive(process.pid) {
+            warn!("{} 服务未启用健康检查，进程已退出", service.name);
+            return Err(HealthFailure::Unhealthy);
+        }
+        info!("{} 服务未启用健康检查，进程存活即视为就绪", service.name);
+        return Ok(serde_json::Value::Null);
+    }
+
+    if health_check.check_type == HealthCheckType::File {
+        return check_ready_file(service, &health_check, cancel)
+            .await
+            .then_some(serde_json::Value::Null)
+            .ok_or(HealthFailure::Unhealthy);
+    }
+    if health_check.check_type == HealthCheckType::Tcp {
+        return check_tcp_address(service, &health_check, cancel)
+            .await
+            .then_some(serde_json::Value::Null)
+            .ok_or(HealthFailure::Unhealthy);
+    }
+    if health_check.check_type == HealthCheckType::Log {
+        return check_ready_log(service, &health_check, log_store, process, cancel)
+            .await
+            .then_some(serde_json::Value::Null)
+            .ok_or(HealthFailure::Unhealthy);
+    }
+    if health_check.check_type == HealthCheckType::Grpc {
+        return check_grpc_health(service, &health_check, cancel)
+            .await
+            .then_some(serde_json::Value::Null)
+            .ok_or(HealthFailure::Unhealthy);
+    }
+
+    if health_check.url.is_empty() {
+        info!("{} 服务健康检查URL为空，跳过检查", service.name);
+        return Ok(serde_json::Value::Null);
+    }
+
+    let health_check_url = match build_health_check_url(&health_check.url, &health_check.endpoint) {
+        Ok(url) => url,
+        Err(e) => {
+            warn!("{} 服务{}", service.name, e);
+            return Err(HealthFailure::Unhealthy);
+        }
+    };
+
+    let auth_bearer = resolve_auth_bearer(service, &health_check)?;
+
+    let client = match health_check_client(&health_check) {
+        Ok(client) => client,
+        Err(e) => {
+            error!("{} 服务健康检查客户端创建失败: {}", service.name, e);
+            return Err(HealthFailure::Unhealthy);
+        }
+    };
+
+    info!(
+        "开始对 {} 服务进行健康检查，URL: {}",
+        service.name, health_check_url
+    );
+
+    let response = retry_probe(service, &health_check, cancel, || async {
+        let request = health_check_request(
+            &client,
+            &health_check_url,
+            auth_bearer.as_deref(),
+            health_check.request_timeout_ms,
+        );
+        let request_started = Instant::now();
+        let result = request.send().await;
+        debug!(
+            "{} 服务健康检查请求 {} 耗时 {:?}: {:?}",
+            service.name,
+            health_check_url,
+            request_started.elapsed(),
+            result.as_ref().map(|response| response.status())
+        );
+        match result {
+            Ok(response) if response.status().is_success() => Ok(response),
+            Ok(response) => {
+                let status = response.status();
+                if let Some(pointer) = &health_check.progress_json_pointer {
+                    report_startup_progress(emitter, service, pointer, response).await;
+                }
+                Err(format!("服务未就绪，状态码: {}", status))
+            }
+            // 连接失败说明服务尚未启动；已连接但超时说明服务已启动但响应缓慢
+            Err(e) if e.is_timeout() && !e.is_connect() => {
+                Err(format!("服务已连接但响应超时: {}", e))
+            }
+            Err(e) => Err(format!("无法连接到服务: {}", e)),
+        }
+    })
+    .await
+    .ok_or(HealthFailure::Unhealthy)?;
+
+    if let Some(min_days) = health_check.min_cert_days_remaining {
+        check_cert_expiry(service, &health_check_url, &response, min_days)?;
+    }
+    Ok(export_health_details(&health_check, response).await)
+}
+
+/// 记录关键服务的失败，只保留最先发生的失败对应的退出码
+fn record_failure(exit_code: &mut i32, service: &ServiceConfig, code: i32) {
+    if service.critical && *exit_code == EXIT_OK {
+        *exit_code = code;
+    }
+}
+
+/// --list 模式：加载并校验配置，以制表符分隔的表格打印所有服务
+fn run_list() -> i32 {
+    let config = match load_services_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("加载配置文件失败: {}", e);
+            return EXIT_CONFIG_ERROR;
+        }
+    };
+    let validation_errors = validate_services_config(&config);
+
+    println!("name\texecutable\tworking_dir\thealth_url\tenabled");
+    for service in &config.services {
+        let health_check = get_health_check_config(service);
+        let health_url = if health_check.enabled && !health_check.url.is_empty() {
+            build_health_check_url(&health_check.url, &health_check.endpoint)
+                .map(|url| url.to_string())
+                .unwrap_or_else(|_| health_check.url.clone())
+        } else {
+            "-".to_string()
+        };
+        // 启动条件不满足的服务不会被启动
+        let enabled = service
+            .condition
+            .as_ref()
+            .is_none_or(|condition| condition.evaluate(Path::new(&service.working_dir)).is_ok());
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            service.name, service.executable, service.working_dir, health_url, enabled
+        );
+    }
+
+    let mut exit_code = EXIT_OK;
+    for (name, e) in &validation_errors {
+        eprintln!("{} 服务配置校验失败: {}", name, e);
+        if e.is_config_error() {
+            exit_code = EXIT_CONFIG_ERROR;
+        }
+    }
+    exit_code
+}
+
+/// 仅校验配置并打印启动计划，不启动任何进程，返回退出码
+fn run_dry_run() -> i32 {
+    let config = match load_services_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("加载配置文件失败: {}", e);
+            return EXIT_CONFIG_ERROR;
+        }
+    };
+
+    let mut exit_code = EXIT_OK;
+
+    for service in &config.services {
+        println!("[计划] 启动 {} 服务", service.name);
+        println!("  - 可执行文件: {}", service.executable);
+        println!("  - 工作目录: {}", service.working_dir);
+        println!("  - 参数: {:?}", service_args(service));
+
+        if let Some(condition) = &service.condition {
+            if let Err(reason) = condition.evaluate(Path::new(&service.working_dir)) {
+                println!("  - 启动条件不满足，将跳过: {}", reason);
+                continue;
+            }
+        }
+
+        if let Err(e) = validate_service(service) {
+            eprintln!("  - 配置校验失败: {}", e);
+            record_failure(&mut exit_code, service, validation_exit_code(&e));
+            continue;
+        }
+
+        let health_check = get_health_check_config(service);
+        if health_check.enabled && !health_check.url.is_empty() {
+            if let Ok(url) = build_health_check_url(&health_check.url, &health_check.endpoint) {
+                println!("  - 健康检查: {}", url);
+            }
+        }
+    }
+
+    exit_code
+}
+
+/// 输出启动报告
+fn print_startup_report(process_manager: &ProcessManager) {
+    println!("===== 启动报告 =====");
+    for status in collect_services_status(process_manager) {
+        println!("{}: {:?}", status.service_name, status.state);
+    }
+}
+
+/// 等待前端就绪，避免早期事件在监听器注册前发出而丢失
+async fn wait_for_frontend(mut frontend_ready: watch::Receiver<bool>) {
+    let wait = frontend_ready.wait_for(|ready| *ready);
+    match tokio::time::timeout(FRONTEND_READY_TIMEOUT, wait).await {
+        Ok(_) => info!("前端已就绪，开始启动服务"),
+        Err(_) => info!(
+            "等待前端就绪超时（{:?}），继续启动服务",
+            FRONTEND_READY_TIMEOUT
+        ),
+    }
+}
+
+/// 单个服务的启动结果
+enum LaunchOutcome {
+    /// 启动条件不满足
+    Skipped,
+    /// 磁盘或内存不满足 preconditions，abort 表示应中止整个启动
+    PreconditionFailed { abort: bool },
+    /// 配置校验未通过
+    Invalid(ServiceError),
+    /// 服务已在启动或运行中
+    AlreadyActive,
+    /// 进程创建失败
+    SpawnFailed,
+    /// 健康检查通过
+    Healthy,
+    /// 健康检查未通过
+    Unhealthy,
+    /// 一次性任务成功完成
+    Completed,
+    /// 一次性任务以非零退出码退出或超时
+    TaskFailed,
+    /// 启动被取消
+    Cancelled,
+    /// 所依赖的服务未就绪或一次性任务未成功完成
+    DependencyFailed,
+}
+
+/// 进程存活检查间隔
+const PROCESS_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+/// 一次性任务退出检查间隔
+const ONESHOT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 记录服务主进程并开始监视其是否意外退出
+fn track_service_process(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    service_name: &str,
+    pid: u32,
+    stdin: Option<ChildStdin>,
+) {
+    let mut persisted_stats = None;
+    if let Some(info) = process_manager.lock().unwrap().get_mut(service_name) {
+        info.pid = Some(pid);
+        info.stdin = stdin.map(|stdin| Arc::new(Mutex::new(stdin)));
+        info.launch_count += 1;
+        info.started_at_ms = Some(unix_timestamp_ms());
+        info.last_activity_ms = info.started_at_ms;
+        if info.launch_count > 1 {
+            info.restart_stats.record(unix_timestamp_ms());
+            if info.persist_restart_stats {
+                persisted_stats = Some(info.restart_stats.clone());
+            }
+        }
+    }
+    if let Some(stats) = persisted_stats {
+        persist::save_restart_stats(service_name, &stats);
+    }
+    async_runtime::spawn(watch_service_exit(
+        app.clone(),
+        process_manager.clone(),
+        service_name.to_string(),
+        pid,
+    ));
+}
+
+fn is_monitoring_paused(process_manager: &ProcessManager, service_name: &str) -> bool {
+    let manager = process_manager.lock().unwrap();
+    manager
+        .get(service_name)
+        .is_some_and(|info| info.monitoring_paused)
+}
+
+/// 设置指定服务（None 为全部服务）的维护模式，返回状态实际发生变化的服务
+fn set_monitoring_paused(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    name: Option<String>,
+    paused: bool,
+) -> Result<Vec<String>, String> {
+    let changed: Vec<String> = {
+        let mut manager = process_manager.lock().unwrap();
+        if let Some(name) = &name {
+            if !manager.contains_key(name) {
+                return Err(format!("未找到服务: {}", name));
+            }
+        }
+        manager
+            .iter_mut()
+            .filter(|(service_name, _)| name.as_ref().is_none_or(|name| name == *service_name))
+            .filter(|(_, info)| info.monitoring_paused != paused)
+            .map(|(service_name, info)| {
+                info.monitoring_paused = paused;
+                service_name.clone()
+            })
+            .collect()
+    };
+
+    if !changed.is_empty() {
+        info!(
+            "{} 服务进程监视: {:?}",
+            if paused { "暂停" } else { "恢复" },
+            changed
+        );
+        let event = if paused {
+            ServiceEvent::MonitoringPaused
+        } else {
+            ServiceEvent::MonitoringResumed
+        };
+        emit_service_event(
+            app,
+            event,
+            MonitoringData {
+                services: changed.clone(),
+            },
+        );
+    }
+    Ok(changed)
+}
+
+/// 服务主进程仍由 xcontrol 负责且没有被请求停止
+fn is_watched_process(process_manager: &ProcessManager, service_name: &str, pid: u32) -> bool {
+    let manager = process_manager.lock().unwrap();
+    manager.get(service_name).is_some_and(|info| {
+        info.pid == Some(pid) && info.state.has_process() && info.state != ServiceState::Stopping
+    })
+}
+
+/// 监视服务主进程；进程在未请求停止的情况下退出时标记为 Failed 并发送
+/// reason 为 crashed 的 service_stopped 事件。服务被停止、重启或移除后监视结束
+async fn watch_service_exit(
+    app: AppHandle<Wry>,
+    process_manager: ProcessManager,
+    service_name: String,
+    pid: u32,
+) {
+    loop {
+        sleep(PROCESS_WATCH_INTERVAL).await;
+        if !is_watched_process(&process_manager, &service_name, pid) {
+            return;
+        }
+        if is_monitoring_paused(&process_manager, &service_name) || is_process_alive(pid) {
+            continue;
+        }
+        // 检查存活期间可能刚好开始停止，再确认一次
+        if !is_watched_process(&process_manager, &service_name, pid) {
+            return;
+        }
+
+        // 健康检查失败后被保留的进程退出时不自动重启
+        let was_unhealthy = process_manager
+            .lock()
+            .unwrap()
+            .get(&service_name)
+            .is_some_and(|info| info.state == ServiceState::Unhealthy);
+        let exit_state = if job::limit_exceeded(&service_name) {
+            warn!(
+                "{} 服务进程 (PID: {}) 超出资源限制被终止",
+                service_name, pid
+            );
+            let event_data = ServiceEventData {
+                service_name: service_name.clone(),
+                url: String::new(),
+                public_url: String::new(),
+                error: "服务超出资源限制被终止".to_string(),
+                status: "resource_limit".to_string(),
+                pid: Some(pid),
+            };
+            emit_service_event(&app, ServiceEvent::Error, event_data);
+            // 与其他终止方式一样走停止流程，清理残留进程并发送 service_stopped
+            let stop_app = app.clone();
+            let stop_manager = process_manager.clone();
+            let stop_name = service_name.clone();
+            let _ = async_runtime::spawn_blocking(move || {
+                stop_service_blocking(
+                    &stop_app,
+                    &stop_manager,
+                    &stop_name,
+                    StopReason::ResourceLimit,
+                )
+            })
+            .await;
+            ServiceState::Stopped
+        } else {
+            warn!("{} 服务进程 (PID: {}) 意外退出", service_name, pid);
+            let _ = transition_service_state(
+                &app,
+                &process_manager,
+                &service_name,
+                ServiceState::Failed,
+            );
+            if let Some(info) = process_manager.lock().unwrap().get_mut(&service_name) {
+                info.pid = None;
+                info.stdin = None;
+                info.started_at_ms = None;
+            }
+            job::close(&service_name);
+            persist::remove_record(&service_name);
+            emit_service_event(
+                &app,
+                ServiceEvent::Stopped,
+                ServiceStoppedData {
+                    service_name: service_name.clone(),
+                    reason: StopReason::Crashed,
+                },
+            );
+            ServiceState::Failed
+        };
+        async_runtime::spawn(auto_restart_service(
+            app,
+            process_manager,
+            service_name,
+            exit_state,
+            was_unhealthy,
+        ));
+        return;
+    }
+}
+
+/// 配置了 auto_restart 的服务崩溃后按退避等待并重新启动；窗口期内自动重启次数达到上限时熔断，
+/// 服务保持退出后的状态并发送 service_restart_circuit_open，直到手动重启。
+/// exit_state 为进程退出后服务所处的状态（崩溃为 Failed，超出资源限制为 Stopped）
+async fn auto_restart_service(
+    app: AppHandle<Wry>,
+    process_manager: ProcessManager,
+    service_name: String,
+    exit_state: ServiceState,
+    was_unhealthy: bool,
+) {
+    let config = match load_services_config() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("加载配置文件失败，{} 服务不会自动重启: {}", service_name, e);
+            return;
+        }
+    };
+    let Some(service) = config
+        .services
+        .iter()
+        .find(|s| s.name == service_name && s.auto_restart)
+        .filter(|s| !(s.keep_on_failure && was_unhealthy))
+    else {
+        return;
+    };
+
+    let delay = {
+        let mut manager = process_manager.lock().unwrap();
+        let Some(info) = manager.get_mut(&service_name) else {
+            return;
+        };
+        let window_ms = Duration::from_millis(service.restart_burst_window_ms);
+        if !info
+            .restart_circuit
+            .try_restart(service.restart_burst_limit, window_ms)
+        {
+            drop(manager);
+            warn!(
+                "{} 服务在 {}ms 内已自动重启 {} 次，停止自动重启",
+                service_name, service.restart_burst_window_ms, service.restart_burst_limit
+            );
+            emit_service_event(
+                &app,
+                ServiceEvent::RestartCircuitOpen,
+                RestartCircuitOpenData {
+                    service_name,
+                    restart_burst_limit: service.restart_burst_limit,
+                    restart_burst_window_ms: service.restart_burst_window_ms,
+                },
+            );
+            return;
+        }
+        info.restart_backoff.next_delay()
+    };
+    if !delay.is_zero() {
+        info!("{} 服务 {:?} 后自动重启", service_name, delay);
+        sleep(delay).await;
+    }
+
+    let service_locks = app.state::<ServiceLockStore>();
+    let _guard = match service_locks.try_lock(&service_name) {
+        Ok(guard) => guard,
+        Err(e) => {
+            warn!("跳过自动重启: {}", e);
+            return;
+        }
+    };
+    // 等待期间已被手动启动或停止的服务不再自动重启
+    let still_exited = process_manager
+        .lock()
+        .unwrap()
+        .get(&service_name)
+        .is_some_and(|info| info.state == exit_state && info.pid.is_none());
+    if !still_exited {
+        return;
+    }
+
+    info!("{} 服务意外退出，自动重启", service_name);
+    let log_store = app.state::<LogStore>();
+    relaunch_service(&app, &process_manager, &log_store, &config, service).await;
+    if let Err(e) = restart_dependent_services(
+        &app,
+        &process_manager,
+        &log_store,
+        &config,
+        &service_name,
+        &[],
+    )
+    .await
+    {
+        error!("重启依赖 {} 的服务失败: {}", service_name, e);
+    }
+}
+
+/// 手动启动或重启服务时恢复自动重启
+fn clear_restart_circuit(process_manager: &ProcessManager, name: &str) {
+    if let Some(info) = process_manager.lock().unwrap().get_mut(name) {
+        info.restart_circuit.reset();
+    }
+}
+
+/// 服务健康检查通过后迁移到 Healthy
+fn report_service_ready(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    service: &ServiceConfig,
+    details: serde_json::Value,
+) -> LaunchOutcome {
+    let _ = transition_service_state(app, process_manager, &service.name, ServiceState::Healthy);
+    let health_check = get_health_check_config(service);
+    let pid = process_manager
+        .lock()
+        .unwrap()
+        .get(&service.name)
+        .and_then(|info| info.pid);
+    let event_data = ServiceEventData {
+        service_name: service.name.clone(),
+        url: health_check.url.clone(),
+        public_url: service
+            .public_url
+            .clone()
+            .unwrap_or_else(|| health_check.url.clone()),
+        error: String::new(),
+        status: "ready".to_string(),
+        pid,
+    };
+    emit_service_event(
+        app,
+        ServiceEvent::Ready,
+        ServiceReadyData {
+            event: event_data,
+            details,
+        },
+    );
+    LaunchOutcome::Healthy
+}
+
+/// 等待一次性任务退出：退出码为 0 时标记为 completed 并发送 service_ready，
+/// 非零退出码或超时标记为 failed 并发送 service_error；任务不受崩溃检测监视
+async fn wait_oneshot_task(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    service: &ServiceConfig,
+    mut child: Child,
+    cancel: &CancellationToken,
+) -> LaunchOutcome {
+    let pid = child.id();
+    let deadline = Instant::now() + Duration::from_millis(service.oneshot_timeout_ms);
+    let result = loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => break Ok(()),
+            Ok(Some(status)) => break Err(format!("任务退出码: {}", status)),
+            Ok(None) => {}
+            Err(e) => break Err(format!("等待任务退出失败: {}", e)),
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            break Err(format!("任务超过 {}ms 未退出", service.oneshot_timeout_ms));
+        }
+        // 取消后由 abort_partial_startup 终止任务进程
+        tokio::select! {
+            _ = sleep(ONESHOT_POLL_INTERVAL) => {}
+            _ = cancel.cancelled() => return LaunchOutcome::Cancelled,
+        }
+    };
+    job::close(&service.name);
+    if let Some(info) = process_manager.lock().unwrap().get_mut(&service.name) {
+        info.stdin = None;
+    }
+
+    match result {
+        Ok(()) => {
+            info!("{} 一次性任务已完成", service.name);
+            let _ = transition_service_state(
+                app,
+                process_manager,
+                &service.name,
+                ServiceState::Completed,
+            );
+            let event_data = ServiceEventData {
+                service_name: service.name.clone(),
+                url: String::new(),
+                public_url: String::new(),
+                error: String::new(),
+                status: "completed".to_string(),
+                pid: Some(pid),
+            };
+            emit_service_event(app, ServiceEvent::Ready, event_data);
+            LaunchOutcome::Completed
+        }
+        Err(e) => {
+            error!("{} 一次性任务失败: {}", service.name, e);
+            let _ =
+                transition_service_state(app, process_manager, &service.name, ServiceState::Failed);
+            let event_data = ServiceEventData {
+                service_name: service.name.clone(),
+                url: String::new(),
+                public_url: String::new(),
+                error: e,
+                status: "error".to_string(),
+                pid: Some(pid),
+            };
+            emit_service_event(app, ServiceEvent::Error, event_data);
+            LaunchOutcome::TaskFailed
+        }
+    }
+}
+
+fn emit_service_starting(app: &AppHandle<Wry>, service: &ServiceConfig, pid: Option<u32>) {
+    let event_data = ServiceEventData {
+        service_name: service.name.clone(),
+        url: String::new(),
+        public_url: String::new(),
+        error: String::new(),
+        status: "starting".to_string(),
+        pid,
+    };
+    emit_service_event(app, ServiceEvent::Starting, event_data);
+}
+
+/// 启动单个服务并等待健康检查，全程向前端发送对应事件
+async fn launch_service(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    service: &ServiceConfig,
+    validation_error: Option<ServiceError>,
+    global_preconditions: Option<&Preconditions>,
+    cancel: &CancellationToken,
+) -> LaunchOutcome {
+    info!("处理服务: {}", service.name);
+    info!("  - 可执行文件: {}", service.executable);
+    info!("  - 工作目录: {}", service.working_dir);
+    info!("  - 窗口模式: {:?}", service_window_mode(service));
+    info!("  - 参数: {:?}", service_args(service));
+
+    // 打印健康检查配置
+    let health_check = get_health_check_config(service);
+    info!(
+        "  - 健康检查: enabled={}, url={}",
+        health_check.enabled, health_check.url
+    );
+
+    // 启动条件不满足时跳过该服务
+    if let Some(condition) = &service.condition {
+        if let Err(reason) = condition.evaluate(Path::new(&service.working_dir)) {
+            info!("{} 服务启动条件不满足，跳过: {}", service.name, reason);
+            let event_data = ServiceEventData {
+                service_name: service.name.clone(),
+                url: String::new(),
+                public_url: String::new(),
+                error: reason,
+                status: "skipped_condition".to_string(),
+                pid: None,
+            };
+            emit_service_event(app, ServiceEvent::Skipped, event_data);
+            return LaunchOutcome::Skipped;
+        }
+    }
+
+    // 配置校验未通过的服务不启动
+    if let Some(e) = validation_error {
+        error!("{} 服务配置校验失败: {}", service.name, e);
+        let event_data = ServiceEventData {
+            service_name: service.name.clone(),
+            url: String::new(),
+            public_url: String::new(),
+            error: format!("配置错误: {}", e),
+            status: "error".to_string(),
+            pid: None,
+        };
+        emit_service_event(app, ServiceEvent::Error, event_data);
+        return LaunchOutcome::Invalid(e);
+    }
+
+    // 磁盘或内存不足时启动数据类服务可能损坏数据，跳过该服务
+    let preconditions = Preconditions::merged(global_preconditions, service.preconditions.as_ref());
+    if let Err(reason) = preconditions.check(Path::new(&service.working_dir)) {
+        warn!("{} 服务启动前检查未通过，跳过: {}", service.name, reason);
+        let event_data = ServiceEventData {
+            service_name: service.name.clone(),
+            url: String::new(),
+            public_url: String::new(),
+            error: reason,
+            status: "precondition_failed".to_string(),
+            pid: None,
+        };
+        emit_service_event(app, ServiceEvent::Skipped, event_data);
+        return LaunchOutcome::PreconditionFailed {
+            abort: preconditions.aborts_startup(),
+        };
+    }
+
+    // 已在启动或运行中的服务不允许重复启动
+    if let Err(e) =
+        transition_service_state(app, process_manager, &service.name, ServiceState::Starting)
+    {
+        warn!("跳过启动: {}", e);
+        return LaunchOutcome::AlreadyActive;
+    }
+
+    // 通知前端服务正在启动，进程启动后再次发送并附带 PID
+    emit_service_starting(app, service, None);
+
+    // xcontrol 异常退出后重新启动时，上次启动的进程仍在运行且健康则直接接管
+    if let Some(record) = adoptable_process(service) {
+        info!(
+            "发现上次启动的 {} 服务进程仍在运行 (PID: {}，启动于 {})，尝试接管",
+            service.name, record.pid, record.started_at_ms
+        );
+        let health = check_service_health(app, log_store, service, &record, cancel).await;
+        if !cancel.is_cancelled() {
+            record_health_result(process_manager, &service.name, health.is_ok());
+        }
+        if let Ok(details) = health {
+            info!("已接管 {} 服务进程 (PID: {})", service.name, record.pid);
+            attach_service_job(service, record.pid);
+            track_service_process(app, process_manager, &service.name, record.pid, None);
+            // 运行时长从进程实际启动时算起
+            if let Some(info) = process_manager.lock().unwrap().get_mut(&service.name) {
+                info.started_at_ms = Some(record.started_at_ms);
+            }
+            return report_service_ready(app, process_manager, service, details);
+        }
+        if cancel.is_cancelled() {
+            return LaunchOutcome::Cancelled;
+        }
+        info!(
+            "上次启动的 {} 服务进程未通过健康检查，终止后重新启动",
+            service.name
+        );
+        kill_process_by_pid(record.pid);
+    }
+
+    // 启动服务进程；启动时间在创建进程前记录，不会晚于其第一行输出。
+    // 清理同名进程与重试创建进程会阻塞线程，放到阻塞线程池中执行，不拖慢同一批次的其他服务
+    let launched_at_ms = unix_timestamp_ms();
+    let spawn_service = service.clone();
+    let spawn_log_store = log_store.clone();
+    let spawned = async_runtime::spawn_blocking(move || {
+        spawn_service_process(&spawn_service, spawn_log_store)
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string().into()));
+    match spawned {
+        Ok(SpawnedProcess {
+            pid,
+            stdin,
+            child: Some(child),
+        }) if service.kind == ServiceKind::Oneshot => {
+            attach_service_job(service, pid);
+            if let Some(info) = process_manager.lock().unwrap().get_mut(&service.name) {
+                info.stdin = stdin.map(|stdin| Arc::new(Mutex::new(stdin)));
+            }
+            wait_oneshot_task(app, process_manager, service, child, cancel).await
+        }
+        Ok(SpawnedProcess { pid, stdin, .. }) => {
+            attach_service_job(service, pid);
+            let process = ServiceRecord {
+                pid,
+                started_at_ms: launched_at_ms,
+            };
+            persist::save_record(&service.name, process.clone());
+            track_service_process(app, process_manager, &service.name, pid, stdin);
+            emit_service_starting(app, service, Some(pid));
+
+            // 等待一小段时间让进程完全启动
+            tokio::select! {
+                _ = sleep(Duration::from_millis(service.startup_delay_ms)) => {}
+                _ = cancel.cancelled() => return LaunchOutcome::Cancelled,
+            }
+
+            // 进行健康检查
+            let health = check_service_health(app, log_store, service, &process, cancel).await;
+            if !cancel.is_cancelled() {
+                record_health_result(process_manager, &service.name, health.is_ok());
+            }
+            if let Ok(details) = health {
+                report_service_ready(app, process_manager, service, details)
+            } else if cancel.is_cancelled() {
+                LaunchOutcome::Cancelled
+            } else {
+                let _ = transition_service_state(
+                    app,
+                    process_manager,
+                    &service.name,
+                    ServiceState::Unhealthy,
+                );
+                // 保留进程时前端不自动关闭应用，进程由手动停止或应用退出时清理
+                let (status, error) = match health {
+                    Err(HealthFailure::CertExpiring(days)) => {
+                        ("cert_expiring", format!("服务证书将在 {} 天后过期", days))
+                    }
+                    _ if service.keep_on_failure => {
+                        info!(
+                            "{} 服务健康检查失败，按 keep_on_failure 保留进程 (PID: {})",
+                            service.name, pid
+                        );
+                        ("degraded", "服务启动超时或健康检查失败".to_string())
+                    }
+                    _ => ("error", "服务启动超时或健康检查失败".to_string()),
+                };
+                let event_data = ServiceEventData {
+                    service_name: service.name.clone(),
+                    url: String::new(),
+                    public_url: String::new(),
+                    error,
+                    status: status.to_string(),
+                    pid: Some(pid),
+                };
+                emit_service_event(app, ServiceEvent::Error, event_data);
+                LaunchOutcome::Unhealthy
+            }
+        }
+        Err(e) => {
+            error!("启动 {} 服务失败: {}", service.name, e);
+            let _ =
+                transition_service_state(app, process_manager, &service.name, ServiceState::Failed);
+            let event_data = ServiceEventData {
+                service_name: service.name.clone(),
+                url: String::new(),
+                public_url: String::new(),
+                error: format!("启动服务失败: {}", e),
+                status: "error".to_string(),
+                pid: None,
+            };
+            emit_service_event(app, ServiceEvent::Error, event_data);
+            LaunchOutcome::SpawnFailed
+        }
+    }
+}
+
+/// 启动所有服务并通知前端，返回 --verify-only 模式下使用的退出码
+async fn start_all_services_and_notify(
+    app: AppHandle<Wry>,
+    process_manager: ProcessManager,
+    log_store: LogStore,
+    frontend_ready: watch::Receiver<bool>,
+    present: bool,
+) -> i32 {
+    wait_for_frontend(frontend_ready).await;
+
+    // 打包后的应用没有控制台，把查找过的路径发给前端，便于排查找不到配置文件的问题
+    emit_service_event(&app, ServiceEvent::ConfigSearch, config_search_report());
+    let config = match load_services_config() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("加载配置文件失败: {}", e);
+            let event_data = ServiceEventData {
+                service_name: "config".to_string(),
+                url: String::new(),
+                public_url: String::new(),
+                error: format!("加载配置文件失败: {}", e),
+                status: "error".to_string(),
+                pid: None,
+            };
+            emit_service_event(&app, ServiceEvent::Error, event_data);
+            if present {
+                reveal_main_window(&app, None);
+            }
+            return EXIT_CONFIG_ERROR;
+        }
+    };
+    let _ = MAIN_WINDOW_LABEL.set(config.main_window_label.clone());
+    let _ = DUPLICATE_INSTANCE_ACTION.set(config.duplicate_instance_action);
+
+    async_runtime::spawn(forward_service_logs(
+        app.clone(),
+        log_store.clone(),
+        // interval 不接受 0
+        Duration::from_millis(config.log_batch_interval_ms.max(1)),
+    ));
+
+    *EXIT_CLEANUP.lock().unwrap() = Some(config.exit_cleanup_actions());
+
+    if let Some(admin_config) = config.admin_server.clone().filter(|c| c.enabled) {
+        async_runtime::spawn(admin::serve(
+            admin_config,
+            process_manager.clone(),
+            log_store.clone(),
+        ));
+    }
+
+    for message in config.warnings() {
+        emit_service_event(
+            &app,
+            ServiceEvent::ConfigWarning,
+            ConfigWarningData { message },
+        );
+    }
+
+    // 配置了启动窗口时主窗口保持隐藏，直到启动完成
+    let splash = if present {
+        let splash = config
+            .splash
+            .as_ref()
+            .and_then(|splash| open_splash_window(&app, splash));
+        if splash.is_none() {
+            reveal_main_window(&app, None);
+        }
+        splash
+    } else {
+        None
+    };
+
+    if let Some(network) = &config.wait_for_network {
+        wait_for_network(&app, network).await;
+    }
+
+    let exit_code = launch_all_services(&app, &process_manager, &log_store, &config).await;
+    emit_service_event(
+        &app,
+        ServiceEvent::StartupComplete,
+        StartupCompleteData { exit_code },
+    );
+    if splash.is_some() {
+        reveal_main_window(&app, splash);
+    }
+    exit_code
+}
+
+/// 列出配置文件的所有候选路径及其是否存在
+fn config_search_report() -> ConfigSearchData {
+    let candidates: Vec<ConfigCandidate> = config_search_paths()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| ConfigCandidate {
+            exists: path.exists(),
+            path: std::path::absolute(&path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned(),
+        })
+        .collect();
+    let found = candidates
+        .iter()
+        .find(|candidate| candidate.exists)
+        .map(|candidate| candidate.path.clone());
+    ConfigSearchData { candidates, found }
+}
+
+/// 创建启动窗口，失败时返回 None 并退回单窗口模式
+fn open_splash_window(app: &AppHandle<Wry>, splash: &SplashConfig) -> Option<WebviewWindow> {
+    let result =
+        WebviewWindowBuilder::new(app, "splash", WebviewUrl::App(splash.url.clone().into()))
+            .title("XControl")
+            .inner_size(splash.width, splash.height)
+            .resizable(false)
+            .decorations(false)
+            .center()
+            .build();
+
+    match result {
+        Ok(splash) => Some(splash),
+        Err(e) => {
+            error!("创建启动窗口失败，直接显示主窗口: {}", e);
+            None
+        }
+    }
+}
+
+/// 主窗口的 label，按配置的 main_window_label 设置；配置加载前为默认值
+static MAIN_WINDOW_LABEL: OnceLock<String> = OnceLock::new();
+
+fn main_window_label() -> &'static str {
+    MAIN_WINDOW_LABEL
+        .get()
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_MAIN_WINDOW_LABEL)
+}
+
+/// 关闭启动窗口并显示主窗口，找不到主窗口时只记录日志
+fn reveal_main_window(app: &AppHandle<Wry>, splash: Option<WebviewWindow>) {
+    match app.get_webview_window(main_window_label()) {
+        Some(window) => {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        None => warn!(
+            "找不到主窗口 {}，请检查 main_window_label 配置",
+            main_window_label()
+        ),
+    }
+    // destroy 不会触发 CloseRequested，避免走退出流程
+    if let Some(splash) = splash {
+        let _ = splash.destroy();
+    }
+}
+
+/// 启动前检查系统中同名进程能否被终止，无法终止的发送 privilege_warning 事件，
+/// 否则清理和停止服务时会失败
+fn warn_inaccessible_processes(app: &AppHandle<Wry>, config: &ServicesConfig) {
+    let elevated = privilege::is_elevated();
+    for service in config.services.iter().filter(|s| !s.shell) {
+        let Ok(pids) = get_processes_by_name(&service.executable, service.process_match) else {
+            continue;
+        };
+        let denied: Vec<u32> = pids
+            .into_iter()
+            .filter(|pid| privilege::check_process_access(*pid) == ProcessAccess::AccessDenied)
+            .collect();
+        if denied.is_empty() {
+            continue;
+        }
+
+        warn!(
+            "{} 服务的同名进程 {:?} 无法被当前权限终止（管理员权限: {}）",
+            service.name, denied, elevated
+        );
+        emit_service_event(
+            app,
+            ServiceEvent::PrivilegeWarning,
+            PrivilegeWarningData {
+                service_name: service.name.clone(),
+                pids: denied,
+                elevated,
+            },
+        );
+    }
+}
+
+/// 全部启动中启动单个服务，所依赖的服务或一次性任务未就绪时不启动
+async fn launch_tier_service(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    config: &ServicesConfig,
+    service: &ServiceConfig,
+    validation_error: Option<ServiceError>,
+    cancel: &CancellationToken,
+) -> LaunchOutcome {
+    // 所依赖的一次性任务失败或被跳过时不启动
+    let unmet = if let Some(task) = unfinished_task_dependency(process_manager, config, service) {
+        warn!(
+            "{} 服务所依赖的一次性任务 {} 未完成，跳过",
+            service.name, task
+        );
+        Some(format!("所依赖的一次性任务 {} 未成功完成", task))
+    } else if let Some(dep) = unready_service_dependency(process_manager, service) {
+        warn!("{} 服务所依赖的服务 {} 未就绪，跳过", service.name, dep);
+        Some(format!("所依赖的服务 {} 未就绪", dep))
+    } else {
+        None
+    };
+    if let Some(error) = unmet {
+        let event_data = ServiceEventData {
+            service_name: service.name.clone(),
+            url: String::new(),
+            public_url: String::new(),
+            error,
+            status: "error".to_string(),
+            pid: None,
+        };
+        emit_service_event(app, ServiceEvent::Error, event_data);
+        return LaunchOutcome::DependencyFailed;
+    }
+
+    launch_service(
+        app,
+        process_manager,
+        log_store,
+        service,
+        validation_error,
+        config.preconditions.as_ref(),
+        cancel,
+    )
+    .await
+}
+
+/// 按依赖关系分批启动全部服务，同一批内的服务按 start_priority 错开后并发启动与健康检查，
+/// 一批全部结束后再启动下一批；返回与 --verify-only 相同含义的退出码
+async fn launch_all_services(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    config: &ServicesConfig,
+) -> i32 {
+    let mut exit_code = EXIT_OK;
+
+    info!("开始启动 {} 个服务", config.services.len());
+
+    for service in &config.services {
+        register_service(process_manager, service, config);
+    }
+    order_services(process_manager, &config.services);
+
+    warn_inaccessible_processes(app, config);
+
+    // 在触碰任何进程之前完成全部配置校验
+    let mut validation_errors = validate_services_config(config);
+    // 以 --detach 启动时所有服务都独立运行，同样不能依赖输出与标准输入
+    if DETACH_ALL.load(std::sync::atomic::Ordering::Relaxed) {
+        for service in &config.services {
+            if let Err(e) = validate_detached(service) {
+                validation_errors.entry(service.name.clone()).or_insert(e);
+            }
+        }
+    }
+
+    // 整体启动超时：到期后取消令牌，中断等待与健康检查
+    let cancel = CancellationToken::new();
+    let startup_timer = config.global_startup_timeout_ms.map(|timeout_ms| {
+        let cancel = cancel.clone();
+        async_runtime::spawn(async move {
+            sleep(Duration::from_millis(timeout_ms)).await;
+            cancel.cancel();
+        })
+    });
+    // 启动前检查要求中止时取消，尚未开始的服务不再启动，已开始的照常完成
+    let abort = cancel.child_token();
+
+    for tier in launch_tiers(&config.services) {
+        if abort.is_cancelled() {
+            break;
+        }
+        let (lazy, tier): (Vec<_>, Vec<_>) = tier.into_iter().partition(|s| s.lazy);
+        for service in lazy {
+            info!("{} 为 lazy 服务，首次请求时再启动", service.name);
+        }
+
+        // 错开同一批各服务的启动时间，避免同时争抢共享资源
+        let stagger = || {
+            if config.start_stagger_ms > 0 {
+                launch::start_stagger_delay(config.start_stagger_ms)
+            } else {
+                Duration::ZERO
+            }
+        };
+        let outcomes = launch::run_staggered(tier.clone(), stagger, &abort, |service| {
+            let validation_error = validation_errors.get(&service.name).cloned();
+            launch_tier_service(
+                app,
+                process_manager,
+                log_store,
+                config,
+                service,
+                validation_error,
+                &cancel,
+            )
+        })
+        .await;
+
+        for (service, outcome) in tier.into_iter().zip(outcomes) {
+            match outcome {
+                Some(LaunchOutcome::Invalid(e)) => {
+                    record_failure(&mut exit_code, service, validation_exit_code(&e))
+                }
+                Some(LaunchOutcome::SpawnFailed) => {
+                    record_failure(&mut exit_code, service, EXIT_SPAWN_FAILURE)
+                }
+                Some(
+                    LaunchOutcome::Unhealthy
+                    | LaunchOutcome::TaskFailed
+                    | LaunchOutcome::DependencyFailed,
+                ) => record_failure(&mut exit_code, service, EXIT_HEALTH_FAILURE),
+                Some(LaunchOutcome::PreconditionFailed {
+                    abort: abort_startup,
+                }) => {
+                    record_failure(&mut exit_code, service, EXIT_SPAWN_FAILURE);
+                    if abort_startup {
+                        warn!("{} 服务启动前检查未通过，中止启动", service.name);
+                        abort.cancel();
+                    }
+                }
+                None
+                | Some(
+                    LaunchOutcome::Cancelled
+                    | LaunchOutcome::Skipped
+                    | LaunchOutcome::AlreadyActive
+                    | LaunchOutcome::Healthy
+                    | LaunchOutcome::Completed,
+                ) => {}
+            }
+        }
+    }
+
+    if let Some(timer) = startup_timer {
+        timer.abort();
+    }
+
+    if cancel.is_cancelled() {
+        let timeout_ms = config.global_startup_timeout_ms.unwrap_or_default();
+        warn!("整体启动超过 {}ms，中止启动", timeout_ms);
+        let terminated = abort_partial_startup(app, process_manager).await;
+        emit_service_event(
+            app,
+            ServiceEvent::StartupTimeout,
+            StartupTimeoutData {
+                timeout_ms,
+                terminated,
+            },
+        );
+        return EXIT_STARTUP_TIMEOUT;
+    }
+
+    exit_code
+}
+
+/// 终止所有尚未就绪但已有进程的服务，返回被终止的服务名
+async fn abort_partial_startup(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+) -> Vec<String> {
+    let services: Vec<String> = {
+        let manager = process_manager.lock().unwrap();
+        manager
+            .iter()
+            .filter(|(_, info)| info.state.has_process() && info.state != ServiceState::Healthy)
+            .map(|(name, _)| name.clone())
+            .collect()
+    };
+
+    for service_name in &services {
+        let app = app.clone();
+        let process_manager = process_manager.clone();
+        let service_name = service_name.clone();
+        let _ = async_runtime::spawn_blocking(move || {
+            stop_service_blocking(&app, &process_manager, &service_name, StopReason::Shutdown)
+        })
+        .await;
+    }
+
+    services
+}
+
+/// 轮询探测网络直到可用或超时，每次失败发送 waiting_for_network；超时后照常启动服务
+async fn wait_for_network(app: &AppHandle<Wry>, network: &NetworkWaitConfig) {
+    if !network::is_valid_target(&network.target) {
+        warn!(
+            "wait_for_network.target 必须为 主机名:端口 或 http(s):// 地址: {}，不等待网络",
+            network.target
+        );
+        return;
+    }
+
+    info!("启动服务前等待网络可用: {}", network.target);
+    let started = Instant::now();
+    let timeout = Duration::from_millis(network.timeout_ms);
+    loop {
+        let error = match network::probe(&network.target).await {
+            Ok(()) => {
+                info!("网络已可用，用时 {:?}", started.elapsed());
+                return;
+            }
+            Err(e) => e,
+        };
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            warn!(
+                "网络在 {}ms 内仍不可用（{}），继续启动服务",
+                network.timeout_ms, error
+            );
+            return;
+        }
+        info!("网络尚不可用: {}", error);
+        emit_service_event(
+            app,
+            ServiceEvent::WaitingForNetwork,
+            WaitingForNetworkData {
+                target: network.target.clone(),
+                elapsed_ms: elapsed.as_millis() as u64,
+                timeout_ms: network.timeout_ms,
+                error,
+            },
+        );
+        sleep(Duration::from_millis(network.retry_interval_ms).min(timeout - elapsed)).await;
+    }
+}
+
+/// 将捕获的服务输出转发给前端
+async fn forward_service_logs(app: AppHandle<Wry>, log_store: LogStore, interval: Duration) {
+    let mut receiver = log_store.subscribe();
+    let mut batcher = LogBatcher::default();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let flush = |batcher: &mut LogBatcher| {
+        for batch in batcher.drain() {
+            emit_service_event(&app, ServiceEvent::LogBatch, batch);
+        }
+    };
+
+    loop {
+        tokio::select! {
+            received = receiver.recv() => match received {
+                Ok(line) => batcher.push(line),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("前端日志转发落后，跳过 {} 行", skipped);
+                    batcher.push_skipped(skipped);
+                }
+                Err(RecvError::Closed) => {
+                    flush(&mut batcher);
+                    break;
+                }
+            },
+            _ = ticker.tick() => flush(&mut batcher),
+        }
+    }
+}
+
+/// 每秒发送一次心跳；每次都会短暂获取进程管理器锁，锁死锁时心跳随之停止
+async fn emit_heartbeats(app: AppHandle<Wry>, process_manager: ProcessManager) {
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    let mut counter: u64 = 0;
+    loop {
+        interval.tick().await;
+        drop(process_manager.lock().unwrap());
+
+        counter += 1;
+        emit_service_event(
+            &app,
+            ServiceEvent::Heartbeat,
+            HeartbeatData {
+                counter,
+                timestamp_ms: unix_timestamp_ms(),
+            },
+        );
+    }
+}
+
+/// 空闲检查间隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 返回运行中且超过 idle_timeout_ms 未被使用的服务
+fn idle_services(process_manager: &ProcessManager, now_ms: u64) -> Vec<String> {
+    let manager = process_manager.lock().unwrap();
+    manager
+        .iter()
+        .filter(|(_, info)| is_idle(info, now_ms))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+fn is_idle(info: &ServiceInfo, now_ms: u64) -> bool {
+    let (Some(timeout), Some(last_activity)) = (info.idle_timeout_ms, info.last_activity_ms) else {
+        return false;
+    };
+    matches!(info.state, ServiceState::Healthy | ServiceState::Unhealthy)
+        && now_ms.saturating_sub(last_activity) >= timeout
+}
+
+/// 定期停止空闲的服务；正在执行其他操作的服务留待下次检查
+async fn stop_idle_services(app: AppHandle<Wry>, process_manager: ProcessManager) {
+    let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        for name in idle_services(&process_manager, unix_timestamp_ms()) {
+            let service_locks = app.state::<ServiceLockStore>();
+            let Ok(_guard) = service_locks.try_lock(&name) else {
+                continue;
+            };
+            // 获取锁期间可能已被 touch_service 或停止
+            let still_idle = process_manager
+                .lock()
+                .unwrap()
+                .get(&name)
+                .is_some_and(|info| is_idle(info, unix_timestamp_ms()));
+            if !still_idle {
+                continue;
+            }
+            info!("{} 服务空闲超时，自动停止", name);
+            let drain = load_services_config()
+                .ok()
+                .and_then(|config| config.services.into_iter().find(|s| s.name == name))
+                .and_then(|service| service.drain);
+            if let Err(e) = stop_running_service(
+                &app,
+                &process_manager,
+                &name,
+                StopReason::Idle,
+                drain.as_ref(),
+            )
+            .await
+            {
+                error!("停止空闲服务失败: {}", e);
+            }
+        }
+    }
+}
+
+/// 记录服务正在被使用，推迟空闲自动停止
+#[tauri::command]
+fn touch_service(name: String, process_manager: State<'_, ProcessManager>) -> Result<(), String> {
+    let mut manager = process_manager.lock().unwrap();
+    let info = manager
+        .get_mut(&name)
+        .ok_or_else(|| format!("未找到服务: {}", name))?;
+    info.last_activity_ms = Some(unix_timestamp_ms());
+    Ok(())
+}
+
+/// 检查监视文件的间隔
+#[cfg(debug_assertions)]
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// 检测到文件变化后等待多久再重启，合并编译、复制等过程中的连续写入
+#[cfg(debug_assertions)]
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// reload_service 的调用次数，--watch 据此重新读取 include 等引入的配置
+static CONFIG_RELOADS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// 判断是否需要重新读取配置：reload_service 的调用次数与各候选 services.dat 的修改时间
+#[cfg(debug_assertions)]
+fn config_signature() -> (u64, Vec<Option<std::time::SystemTime>>) {
+    let modified = config_search_paths()
+        .unwrap_or_default()
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect();
+    (
+        CONFIG_RELOADS.load(std::sync::atomic::Ordering::Relaxed),
+        modified,
+    )
+}
+
+/// --watch 模式下监视各服务 watch 中的文件，变化后重启正在运行的服务；services.dat 被修改或调用
+/// reload_service 后重新读取配置，新增或修改了 watch 的服务以当前文件为基准，不触发重启
+#[cfg(debug_assertions)]
+async fn watch_service_files(app: AppHandle<Wry>, process_manager: ProcessManager) {
+    // 每个服务的监视列表及上次检查时的文件快照
+    let mut snapshots: HashMap<String, (Vec<String>, xcontrol_lib::watch::Snapshot)> =
+        HashMap::new();
+    // 处于防抖等待中的服务，等待期间的后续变化并入同一次重启
+    let debouncing: Arc<Mutex<std::collections::HashSet<String>>> = Arc::default();
+    let mut services: Vec<ServiceConfig> = Vec::new();
+    let mut signature = None;
+
+    let mut interval = tokio::time::interval(WATCH_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let current = config_signature();
+        if signature.as_ref() != Some(&current) {
+            signature = Some(current);
+            match load_services_config() {
+                Ok(config) => {
+                    services = config
+                        .services
+                        .into_iter()
+                        .filter(|service| !service.watch.is_empty())
+                        .collect();
+                    if services.is_empty() {
+                        info!("--watch: 没有配置了 watch 的服务");
+                    }
+                    snapshots.retain(|name, _| services.iter().any(|s| &s.name == name));
+                }
+                // 保留之前的监视列表，配置修正后再重新读取
+                Err(e) => error!("--watch: 加载配置文件失败，沿用之前的监视列表: {}", e),
+            }
+        }
+
+        for service in &services {
+            let snapshot =
+                xcontrol_lib::watch::snapshot(Path::new(&service.working_dir), &service.watch);
+            let changed = match snapshots.get(&service.name) {
+                Some((watch, previous)) => watch == &service.watch && previous != &snapshot,
+                None => false,
+            };
+            snapshots.insert(service.name.clone(), (service.watch.clone(), snapshot));
+            if changed && debouncing.lock().unwrap().insert(service.name.clone()) {
+                spawn_watch_restart(
+                    app.clone(),
+                    process_manager.clone(),
+                    service.name.clone(),
+                    debouncing.clone(),
+                );
+            }
+        }
+    }
+}
+
+/// 防抖等待后在单独的任务中重启正在运行的服务，不阻塞其他服务的文件检查；
+/// 正在执行其他操作的服务跳过本次重启
+#[cfg(debug_assertions)]
+fn spawn_watch_restart(
+    app: AppHandle<Wry>,
+    process_manager: ProcessManager,
+    name: String,
+    debouncing: Arc<Mutex<std::collections::HashSet<String>>>,
+) {
+    async_runtime::spawn(async move {
+        sleep(WATCH_DEBOUNCE).await;
+        debouncing.lock().unwrap().remove(&name);
+        if !is_service_running(&process_manager, &name) {
+            return;
+        }
+        let service_locks = app.state::<ServiceLockStore>();
+        let Ok(_guard) = service_locks.try_lock(&name) else {
+            return;
+        };
+        info!("{} 服务监视的文件已变化，重新启动", name);
+        let config = match load_services_config() {
+            Ok(config) => config,
+            Err(e) => {
+                error!("加载配置文件失败，跳过重启 {}: {}", name, e);
+                return;
+            }
+        };
+        clear_restart_circuit(&process_manager, &name);
+        let log_store = app.state::<LogStore>();
+        if let Err(e) = restart_service(
+            &app,
+            &process_manager,
+            &log_store,
+            &config,
+            &name,
+            StopReason::User,
+        )
+        .await
+        {
+            error!("重启 {} 服务失败: {}", name, e);
+        }
+    });
+}
+
+/// 前端注册完事件监听器后调用
+#[tauri::command]
+fn frontend_ready(frontend_ready: State<'_, FrontendReady>) {
+    frontend_ready.0.send_replace(true);
+}
+
+/// 重新读取配置文件，仅停止并按新配置重启指定服务，其他服务不受影响
+#[tauri::command]
+async fn reload_service(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<(), String> {
+    let _guard = service_locks.try_lock(&name).map_err(|e| e.to_string())?;
+    let process_manager = process_manager.inner().clone();
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    CONFIG_RELOADS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    clear_restart_circuit(&process_manager, &name);
+
+    restart_service(
+        &app,
+        &process_manager,
+        &log_store,
+        &config,
+        &name,
+        StopReason::User,
+    )
+    .await
+}
+
+/// 服务重启后已就绪且配置了 restart_dependents 时，按依赖顺序重启依赖它的所有服务；
+/// 只重启已登记的服务，skip 中的服务不重启，连续重启时按退避等待，正在执行其他操作的服务跳过
+async fn restart_dependent_services(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    config: &ServicesConfig,
+    name: &str,
+    skip: &[&str],
+) -> Result<(), String> {
+    let restart_dependents = config
+        .services
+        .iter()
+        .any(|s| s.name == name && s.restart_dependents);
+    let healthy = process_manager
+        .lock()
+        .unwrap()
+        .get(name)
+        .is_some_and(|info| info.state == ServiceState::Healthy);
+    if !restart_dependents || !healthy {
+        return Ok(());
+    }
+
+    let service_locks = app.state::<ServiceLockStore>();
+    for dependent in restart::dependents_in_order(&config.services, name) {
+        if skip.contains(&dependent.as_str()) {
+            continue;
+        }
+        let _guard = match service_locks.try_lock(&dependent) {
+            Ok(guard) => guard,
+            Err(e) => {
+                warn!("跳过重启: {}", e);
+                continue;
+            }
+        };
+        let delay = {
+            let mut manager = process_manager.lock().unwrap();
+            match manager.get_mut(&dependent) {
+                Some(info) => info.restart_backoff.next_delay(),
+                None => continue,
+            }
+        };
+        if !delay.is_zero() {
+            info!("{} 服务近期已重启，{:?} 后再次重启", dependent, delay);
+            sleep(delay).await;
+        }
+
+        info!("{} 依赖的 {} 服务已重启，随之重启", dependent, name);
+        stop_and_relaunch_service(
+            app,
+            process_manager,
+            log_store,
+            config,
+            &dependent,
+            StopReason::Dependency,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// 按旧配置停止服务，再按给定配置重新启动，随后按 restart_dependents 重启依赖它的服务；
+/// 服务已从配置中移除时发送 service_removed
+async fn restart_service(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    config: &ServicesConfig,
+    name: &str,
+    reason: StopReason,
+) -> Result<(), String> {
+    stop_and_relaunch_service(app, process_manager, log_store, config, name, reason).await?;
+    restart_dependent_services(app, process_manager, log_store, config, name, &[]).await
+}
+
+/// 按旧配置停止服务，再按给定配置重新启动，不处理依赖它的服务
+async fn stop_and_relaunch_service(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    config: &ServicesConfig,
+    name: &str,
+    reason: StopReason,
+) -> Result<(), String> {
+    let name = name.to_string();
+
+    // 先停止正在运行的进程
+    let drain = config
+        .services
+        .iter()
+        .find(|s| s.name == name)
+        .and_then(|s| s.drain.as_ref());
+    stop_running_service(app, process_manager, &name, reason, drain).await?;
+
+    let Some(service) = config.services.iter().find(|s| s.name == name) else {
+        info!("{} 服务已从配置中移除", name);
+        process_manager.lock().unwrap().shift_remove(&name);
+        emit_service_event(
+            app,
+            ServiceEvent::Removed,
+            ServiceRemovedData { service_name: name },
+        );
+        return Ok(());
+    };
+
+    info!("按新配置重新启动 {} 服务", name);
+    relaunch_service(app, process_manager, log_store, config, service).await;
+
+    Ok(())
+}
+
+/// 请求服务进入排空模式并发送 service_draining 事件
+async fn drain_running_service(
+    app: &AppHandle<Wry>,
+    name: &str,
+    drain: &DrainConfig,
+) -> Result<(), String> {
+    drain::request(drain)
+        .await
+        .map_err(|e| format!("{} 服务{}", name, e))?;
+    info!("{} 服务已进入排空模式", name);
+    emit_service_event(
+        app,
+        ServiceEvent::Draining,
+        ServiceDrainingData {
+            service_name: name.to_string(),
+            drain_wait_ms: drain.drain_wait_ms,
+        },
+    );
+    Ok(())
+}
+
+fn is_service_running(process_manager: &ProcessManager, name: &str) -> bool {
+    let manager = process_manager.lock().unwrap();
+    manager
+        .get(name)
+        .is_some_and(|info| info.state.has_process())
+}
+
+/// 停止正在运行的服务进程，未运行时什么也不做；配置了排空时先排空并等待
+async fn stop_running_service(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    name: &str,
+    reason: StopReason,
+    drain: Option<&DrainConfig>,
+) -> Result<(), String> {
+    let is_running = is_service_running(process_manager, name);
+    if let Some(drain) = drain.filter(|_| is_running) {
+        // 排空失败不阻止停止
+        match drain_running_service(app, name, drain).await {
+            Ok(()) => {
+                info!("等待 {}ms 后停止 {} 服务", drain.drain_wait_ms, name);
+                sleep(Duration::from_millis(drain.drain_wait_ms)).await;
+            }
+            Err(e) => warn!("{}，直接停止", e),
+        }
+    }
+    if is_running {
+        let stop_app = app.clone();
+        let stop_manager = process_manager.clone();
+        let stop_name = name.to_string();
+        async_runtime::spawn_blocking(move || {
+            stop_service_blocking(&stop_app, &stop_manager, &stop_name, reason)
+        })
+        .await
+        .map_err(|e| format!("停止 {} 服务失败: {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// 按给定配置重新登记并启动服务
+async fn relaunch_service(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    config: &ServicesConfig,
+    service: &ServiceConfig,
+) -> LaunchOutcome {
+    register_service(process_manager, service, config);
+    order_services(process_manager, &config.services);
+    launch_service(
+        app,
+        process_manager,
+        log_store,
+        service,
+        validate_service(service).err(),
+        config.preconditions.as_ref(),
+        &CancellationToken::new(),
+    )
+    .await
+}
+
+/// 设置服务进程的调度优先级，失败时只记录，不影响启动
+fn apply_service_priority(service: &ServiceConfig, pid: u32) {
+    if service.priority == ProcessPriority::Normal {
+        return;
+    }
+    match priority::apply(pid, service.priority) {
+        Ok(()) => info!(
+            "{} 服务进程优先级已设为 {:?}",
+            service.name, service.priority
+        ),
+        Err(e) => error!("设置 {} 服务进程优先级失败: {}", service.name, e),
+    }
+}
+
+/// 服务实际使用的启动命令，参数中的 secret:// 引用保持原样不解析
+#[derive(Serialize)]
+struct LaunchInfo {
+    service_name: String,
+    executable: String, // 解析后的可执行文件路径，shell 模式下为配置的命令
+    args: Vec<String>,  // 追加平台参数后的完整参数列表
+    working_dir: String,
+    shell: bool,
+    command_line: String, // 可直接粘贴到终端（Windows 为 cmd）中复现的单行命令
+}
+
+fn launch_info(service: &ServiceConfig) -> LaunchInfo {
+    let args = service_args(service);
+    let program_line = if service.shell {
+        service_shell_command_line(service, &args)
+    } else {
+        let exe_path = service_executable_path(service);
+        shell::build_command_line(&shell::quote_arg(&exe_path.to_string_lossy()), &args)
+    };
+    let cd = if cfg!(windows) { "cd /d" } else { "cd" };
+    let command_line = format!(
+        "{} {} && {}",
+        cd,
+        shell::quote_arg(&service.working_dir),
+        program_line
+    );
+
+    LaunchInfo {
+        service_name: service.name.clone(),
+        executable: if service.shell {
+            service.executable.clone()
+        } else {
+            service_executable_path(service)
+                .to_string_lossy()
+                .into_owned()
+        },
+        args,
+        working_dir: service.working_dir.clone(),
+        shell: service.shell,
+        command_line,
+    }
+}
+
+/// 返回服务按当前配置启动时使用的命令，便于排查启动失败
+#[tauri::command]
+fn get_launch_command(name: String) -> Result<LaunchInfo, String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    config
+        .services
+        .iter()
+        .find(|s| s.name == name)
+        .map(launch_info)
+        .ok_or_else(|| format!("未找到服务: {}", name))
+}
+
+/// recheck_service 单次健康检查的超时时间
+const RECHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 立即对运行中的服务做一次健康检查（不重试），按结果在 healthy 与 unhealthy 之间更新状态，
+/// 发送 service_rechecked 事件并返回是否健康
+#[tauri::command]
+async fn recheck_service(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<bool, String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    let mut service = config
+        .services
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("未找到服务: {}", name))?;
+    let (state, pid) = process_manager
+        .lock()
+        .unwrap()
+        .get(&name)
+        .map(|info| (info.state, info.pid))
+        .ok_or_else(|| format!("未找到服务: {}", name))?;
+    let Some(pid) =
+        pid.filter(|_| matches!(state, ServiceState::Healthy | ServiceState::Unhealthy))
+    else {
+        return Err(format!("{} 服务当前状态为 {:?}，无法重新检查", name, state));
+    };
+    // 就绪日志检查只接受本次启动之后的输出
+    let process = persist::load_record(&name)
+        .filter(|record| record.pid == pid)
+        .unwrap_or(ServiceRecord {
+            pid,
+            started_at_ms: 0,
+        });
+
+    service.health_check = Some(HealthCheckConfig {
+        max_retries: 1,
+        ..get_health_check_config(&service)
+    });
+    let cancel = CancellationToken::new();
+    let healthy = tokio::time::timeout(
+        RECHECK_TIMEOUT,
+        check_service_health(
+            &app,
+            app.state::<LogStore>().inner(),
+            &service,
+            &process,
+            &cancel,
+        ),
+    )
+    .await
+    .is_ok_and(|result| result.is_ok());
+    record_health_result(&process_manager, &name, healthy);
+
+    let next = if healthy {
+        ServiceState::Healthy
+    } else {
+        ServiceState::Unhealthy
+    };
+    // 检查期间服务可能已被停止，此时迁移被拒绝，只报告检查结果
+    if state != next {
+        if let Err(e) = transition_service_state(&app, &process_manager, &name, next) {
+            error!("更新 {} 服务状态失败: {}", name, e);
+        }
+    }
+    emit_service_event(
+        &app,
+        ServiceEvent::Rechecked,
+        ServiceRecheckedData {
+            service_name: name,
+            healthy,
+        },
+    );
+    Ok(healthy)
+}
+
+/// 停止单个服务；配置了 drain 时先排空，用于手动停止服务或清理 keep_on_failure 保留的进程
+#[tauri::command]
+async fn stop_service(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<(), String> {
+    let _guard = service_locks.try_lock(&name).map_err(|e| e.to_string())?;
+    if !process_manager.lock().unwrap().contains_key(&name) {
+        return Err(format!("未找到服务: {}", name));
+    }
+    // 配置文件无法加载时不排空，仍然停止
+    let drain = load_services_config()
+        .ok()
+        .and_then(|config| config.services.into_iter().find(|s| s.name == name))
+        .and_then(|service| service.drain);
+    stop_running_service(
+        &app,
+        process_manager.inner(),
+        &name,
+        StopReason::User,
+        drain.as_ref(),
+    )
+    .await
+}
+
+/// 按配置顺序返回分组内的服务，分组不存在时返回错误
+fn group_members<'a>(
+    config: &'a ServicesConfig,
+    group: &str,
+) -> Result<Vec<&'a ServiceConfig>, String> {
+    let members: Vec<_> = config
+        .services
+        .iter()
+        .filter(|s| s.group.as_deref() == Some(group))
+        .collect();
+    if members.is_empty() {
+        return Err(format!("未找到分组: {}", group));
+    }
+    Ok(members)
+}
+
+/// 按依赖顺序启动分组内尚未运行的服务，分组外尚未运行的依赖一并启动并等待就绪；
+/// 依赖未就绪或所依赖的一次性任务未完成时跳过，正在执行其他操作的服务跳过
+async fn start_group_services(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    service_locks: &ServiceLockStore,
+    config: &ServicesConfig,
+    group: &str,
+) -> Result<(), String> {
+    let members = group_members(config, group)?;
+    let mut names: Vec<&str> = Vec::new();
+    for member in &members {
+        for service in dependency_start_order(&config.services, &member.name)? {
+            if !names.contains(&service.name.as_str()) {
+                names.push(&service.name);
+            }
+        }
+    }
+    // 未就绪的服务，依赖它们的服务不再启动
+    let mut not_ready: Vec<&str> = Vec::new();
+    for service in sort_by_dependencies(&config.services, &names)? {
+        let is_member = service.group.as_deref() == Some(group);
+        let _guard = match service_locks.try_lock(&service.name) {
+            Ok(guard) => guard,
+            Err(e) => {
+                warn!("跳过启动: {}", e);
+                not_ready.push(&service.name);
+                continue;
+            }
+        };
+        // 分组外已在运行或已完成的依赖不重新启动
+        let active = process_manager
+            .lock()
+            .unwrap()
+            .get(&service.name)
+            .is_some_and(|info| info.state.has_process() || info.state == ServiceState::Completed);
+        if active && !is_member {
+            continue;
+        }
+        let error = if let Some(dep) = service
+            .depends_on
+            .iter()
+            .find(|dep| not_ready.contains(&dep.as_str()))
+        {
+            Some(format!("所依赖的服务 {} 未就绪", dep))
+        } else {
+            unfinished_task_dependency(process_manager, config, service)
+                .map(|task| format!("所依赖的一次性任务 {} 未成功完成", task))
+        };
+        if let Some(error) = error {
+            warn!("{} 服务{}，跳过", service.name, error);
+            let event_data = ServiceEventData {
+                service_name: service.name.clone(),
+                url: String::new(),
+                public_url: String::new(),
+                error,
+                status: "error".to_string(),
+                pid: None,
+            };
+            emit_service_event(app, ServiceEvent::Error, event_data);
+            not_ready.push(&service.name);
+            continue;
+        }
+        clear_restart_circuit(process_manager, &service.name);
+        match relaunch_service(app, process_manager, log_store, config, service).await {
+            LaunchOutcome::Healthy | LaunchOutcome::Completed | LaunchOutcome::AlreadyActive => {}
+            _ => not_ready.push(&service.name),
+        }
+    }
+    Ok(())
+}
+
+/// 按依赖顺序的逆序停止分组内正在运行的服务，依赖方先于被依赖方停止
+async fn stop_group_services(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    service_locks: &ServiceLockStore,
+    config: &ServicesConfig,
+    group: &str,
+) -> Result<(), String> {
+    let members: Vec<&str> = group_members(config, group)?
+        .into_iter()
+        .map(|service| service.name.as_str())
+        .collect();
+    for service in sort_by_dependencies(&config.services, &members)?
+        .into_iter()
+        .rev()
+    {
+        let _guard = match service_locks.try_lock(&service.name) {
+            Ok(guard) => guard,
+            Err(e) => {
+                warn!("跳过停止: {}", e);
+                continue;
+            }
+        };
+        stop_running_service(
+            app,
+            process_manager,
+            &service.name,
+            StopReason::User,
+            service.drain.as_ref(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// 启动单个服务，先按依赖顺序启动尚未运行的依赖（含 lazy 服务）；服务已在运行时直接返回
+#[tauri::command]
+async fn start_service(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<(), String> {
+    start_service_and_dependencies(
+        &app,
+        process_manager.inner(),
+        &log_store,
+        &service_locks,
+        &name,
+        None,
+    )
+    .await
+}
+
+/// 以临时覆盖的参数、环境变量、窗口模式或可执行文件启动单个服务，用于调试；
+/// 服务已在运行时先停止。覆盖不写入配置文件，下次 start_service、重新加载或自动重启时恢复原配置
+#[tauri::command]
+async fn start_service_with_overrides(
+    name: String,
+    overrides: ServiceConfigOverrides,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<(), String> {
+    start_service_and_dependencies(
+        &app,
+        process_manager.inner(),
+        &log_store,
+        &service_locks,
+        &name,
+        Some(&overrides),
+    )
+    .await
+}
+
+/// 按依赖顺序启动服务及其尚未运行的依赖，overrides 只应用于服务本身
+async fn start_service_and_dependencies(
+    app: &AppHandle<Wry>,
+    process_manager: &ProcessManager,
+    log_store: &LogStore,
+    service_locks: &ServiceLockStore,
+    name: &str,
+    overrides: Option<&ServiceConfigOverrides>,
+) -> Result<(), String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    let order = dependency_start_order(&config.services, name)?;
+    for service in order {
+        let _guard = service_locks
+            .try_lock(&service.name)
+            .map_err(|e| e.to_string())?;
+        // 已在运行或已完成的依赖不重新启动
+        let active = process_manager
+            .lock()
+            .unwrap()
+            .get(&service.name)
+            .is_some_and(|info| info.state.has_process() || info.state == ServiceState::Completed);
+        if active && service.name != name {
+            continue;
+        }
+        if let Some(task) = unfinished_task_dependency(process_manager, &config, service) {
+            return Err(format!(
+                "{} 服务所依赖的一次性任务 {} 未成功完成",
+                service.name, task
+            ));
+        }
+        let overridden = overrides
+            .filter(|_| service.name == name)
+            .map(|overrides| overrides.apply(service));
+        if overridden.is_some() {
+            // 以新参数重新启动正在运行的服务
+            stop_running_service(
+                app,
+                process_manager,
+                name,
+                StopReason::User,
+                service.drain.as_ref(),
+            )
+            .await?;
+        }
+        let service = overridden.as_ref().unwrap_or(service);
+        clear_restart_circuit(process_manager, &service.name);
+        let outcome = relaunch_service(app, process_manager, log_store, &config, service).await;
+        match outcome {
+            LaunchOutcome::Healthy | LaunchOutcome::Completed | LaunchOutcome::AlreadyActive => {}
+            _ if service.name == name => return Err(format!("{} 服务启动失败", name)),
+            _ => return Err(format!("所依赖的服务 {} 启动失败", service.name)),
+        }
+    }
+    Ok(())
+}
+
+/// 启动分组内的所有服务
+#[tauri::command]
+async fn start_group(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<(), String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    start_group_services(
+        &app,
+        process_manager.inner(),
+        &log_store,
+        &service_locks,
+        &config,
+        &name,
+    )
+    .await
+}
+
+/// 停止分组内的所有服务
+#[tauri::command]
+async fn stop_group(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<(), String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    stop_group_services(
+        &app,
+        process_manager.inner(),
+        &service_locks,
+        &config,
+        &name,
+    )
+    .await
+}
 
-    tauri::Builder::default()
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            println!("检测到重复启动，聚焦到现有窗口");
+/// 先停止分组内的所有服务，再按新配置依次启动，随后按 restart_dependents 重启分组外依赖它们的服务
+#[tauri::command]
+async fn restart_group(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<(), String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    let process_manager = process_manager.inner();
+    stop_group_services(&app, process_manager, &service_locks, &config, &name).await?;
+    start_group_services(
+        &app,
+        process_manager,
+        &log_store,
+        &service_locks,
+        &config,
+        &name,
+    )
+    .await?;
+
+    let members: Vec<&str> = group_members(&config, &name)?
+        .into_iter()
+        .map(|service| service.name.as_str())
+        .collect();
+    for member in &members {
+        restart_dependent_services(&app, process_manager, &log_store, &config, member, &members)
+            .await?;
+    }
+    Ok(())
+}
+
+/// 是否需要由 restart_unhealthy 恢复：健康检查未通过或启动、运行失败的服务
+fn needs_recovery(state: ServiceState) -> bool {
+    matches!(state, ServiceState::Unhealthy | ServiceState::Failed)
+}
+
+/// get_restart_stats 返回的重启统计
+#[derive(Serialize)]
+struct RestartStatsInfo {
+    count: u64,
+    last_restart_ms: Option<u64>,
+    circuit_open: bool,
+}
+
+/// 查询服务的重启次数、最近一次重启时间与自动重启是否已熔断
+#[tauri::command]
+fn get_restart_stats(
+    name: String,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<RestartStatsInfo, String> {
+    let manager = process_manager.lock().unwrap();
+    let info = manager
+        .get(&name)
+        .ok_or_else(|| format!("未找到服务: {}", name))?;
+    Ok(RestartStatsInfo {
+        count: info.restart_stats.count,
+        last_restart_ms: info.restart_stats.last_restart_ms,
+        circuit_open: info.restart_circuit.is_open(),
+    })
+}
+
+/// 清零服务的重启统计，同时恢复已熔断的自动重启并清除重启退避
+#[tauri::command]
+fn reset_restart_stats(
+    name: String,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<(), String> {
+    let persist_restart_stats = {
+        let mut manager = process_manager.lock().unwrap();
+        let info = manager
+            .get_mut(&name)
+            .ok_or_else(|| format!("未找到服务: {}", name))?;
+        info.restart_stats = RestartStats::default();
+        info.restart_circuit.reset();
+        info.restart_backoff = RestartBackoff::default();
+        info.persist_restart_stats
+    };
+    if persist_restart_stats {
+        persist::save_restart_stats(&name, &RestartStats::default());
+    }
+    info!("已清零 {} 服务的重启统计", name);
+    Ok(())
+}
+
+/// 恢复已熔断的自动重启：清除熔断状态与重启退避，服务未在运行时按当前配置重新启动，
+/// 并发送 service_restart_circuit_reset；重启统计保持不变
+#[tauri::command]
+async fn reset_restart_circuit(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<(), String> {
+    let _guard = service_locks.try_lock(&name).map_err(|e| e.to_string())?;
+    let process_manager = process_manager.inner().clone();
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    let (was_open, running) = {
+        let mut manager = process_manager.lock().unwrap();
+        let info = manager
+            .get_mut(&name)
+            .ok_or_else(|| format!("未找到服务: {}", name))?;
+        let was_open = info.restart_circuit.is_open();
+        info.restart_circuit.reset();
+        info.restart_backoff = RestartBackoff::default();
+        (was_open, info.state.has_process())
+    };
+    info!("已恢复 {} 服务的自动重启", name);
+
+    if !running {
+        restart_service(
+            &app,
+            &process_manager,
+            &log_store,
+            &config,
+            &name,
+            StopReason::User,
+        )
+        .await?;
+    }
+    emit_service_event(
+        &app,
+        ServiceEvent::RestartCircuitReset,
+        RestartCircuitResetData {
+            service_name: name,
+            was_open,
+            relaunched: !running,
+        },
+    );
+    Ok(())
+}
+
+/// 重启所有处于 unhealthy 或 failed 状态的服务，被依赖的服务先重启；返回已重启的服务
+#[tauri::command]
+async fn restart_unhealthy(
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<Vec<String>, String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    let targets: Vec<String> = process_manager
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, info)| needs_recovery(info.state))
+        .map(|(name, _)| name.clone())
+        // 已从配置中删除的服务需通过 reload_service 处理
+        .filter(|name| config.services.iter().any(|s| &s.name == name))
+        .collect();
+    let names: Vec<&str> = targets.iter().map(String::as_str).collect();
+    let order = sort_by_dependencies(&config.services, &names)?;
+
+    let process_manager = process_manager.inner();
+    let mut restarted = Vec::new();
+    for service in order {
+        let _guard = match service_locks.try_lock(&service.name) {
+            Ok(guard) => guard,
+            Err(e) => {
+                warn!("跳过重启: {}", e);
+                continue;
+            }
+        };
+        // 获取锁之前状态可能已经恢复
+        let still_down = process_manager
+            .lock()
+            .unwrap()
+            .get(&service.name)
+            .is_some_and(|info| needs_recovery(info.state));
+        if !still_down {
+            continue;
+        }
+        if let Some(task) = unfinished_task_dependency(process_manager, &config, service) {
+            warn!(
+                "{} 服务所依赖的一次性任务 {} 未完成，跳过",
+                service.name, task
+            );
+            continue;
+        }
+        info!("重启未就绪的 {} 服务", service.name);
+        clear_restart_circuit(process_manager, &service.name);
+        restart_service(
+            &app,
+            process_manager,
+            &log_store,
+            &config,
+            &service.name,
+            StopReason::User,
+        )
+        .await?;
+        restarted.push(service.name.clone());
+    }
+    Ok(restarted)
+}
+
+/// 检查所有配置了 update_check 的服务是否有待安装的新版本
+#[tauri::command]
+fn check_updates() -> Result<Vec<UpdateInfo>, String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    Ok(config.services.iter().filter_map(update::check).collect())
+}
+
+/// 安装所有待更新的服务：先确认暂存文件可用，再停止服务、替换文件并重新启动，
+/// 返回成功更新的服务
+#[tauri::command]
+async fn apply_updates(
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+    service_locks: State<'_, ServiceLockStore>,
+) -> Result<Vec<UpdateInfo>, String> {
+    let process_manager = process_manager.inner().clone();
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+
+    let mut applied = Vec::new();
+    for service in &config.services {
+        let Some(info) = update::check(service).filter(|info| info.update_available) else {
+            continue;
+        };
+
+        // 暂存文件不可用时保持当前版本继续运行
+        if let Err(e) = update::verify_staged(service) {
+            warn!("{} 服务的更新不可用，跳过: {}", service.name, e);
+            continue;
+        }
+        let _guard = match service_locks.try_lock(&service.name) {
+            Ok(guard) => guard,
+            Err(e) => {
+                warn!("跳过更新: {}", e);
+                continue;
+            }
+        };
+
+        info!(
+            "正在更新 {} 服务: {:?} -> {:?}",
+            service.name, info.current_version, info.staged_version
+        );
+        stop_running_service(
+            &app,
+            &process_manager,
+            &service.name,
+            StopReason::User,
+            service.drain.as_ref(),
+        )
+        .await?;
+        if let Err(e) = update::install(service) {
+            error!("{} 服务更新失败: {}", service.name, e);
+        } else {
+            applied.push(info);
+        }
+        // 无论替换是否成功都重新启动，避免服务因更新失败而停止
+        relaunch_service(&app, &process_manager, &log_store, &config, service).await;
+        restart_dependent_services(
+            &app,
+            &process_manager,
+            &log_store,
+            &config,
+            &service.name,
+            &[],
+        )
+        .await?;
+    }
+
+    Ok(applied)
+}
+
+/// 返回内部服务登记表的完整快照（配置中的明文机密已隐藏），用于排查 xcontrol 自身的问题
+#[tauri::command]
+fn dump_state(process_manager: State<'_, ProcessManager>) -> serde_json::Value {
+    diagnostics::dump_state(&process_manager)
+}
+
+/// 导出诊断包，返回 zip 文件路径
+#[tauri::command]
+async fn export_diagnostics(
+    process_manager: State<'_, ProcessManager>,
+    log_store: State<'_, LogStore>,
+) -> Result<PathBuf, String> {
+    let process_manager = process_manager.inner().clone();
+    let log_store = log_store.inner().clone();
+    async_runtime::spawn_blocking(move || diagnostics::export(&process_manager, &log_store))
+        .await
+        .map_err(|e| format!("导出诊断包失败: {}", e))?
+}
+
+/// 向运行中的服务主进程发送信号：非 Windows 平台为信号名（如 HUP），Windows 为 CTRL_C 或 CTRL_BREAK
+#[tauri::command]
+fn signal_service(
+    name: String,
+    signal: String,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<(), String> {
+    let pid = {
+        let manager = process_manager.lock().unwrap();
+        let info = manager
+            .get(&name)
+            .ok_or_else(|| format!("未找到服务: {}", name))?;
+        info.pid.ok_or_else(|| format!("{} 服务未运行", name))?
+    };
+    info!("向 {} 服务进程 (PID: {}) 发送 {}", name, pid, signal);
+    signal::send_named(pid, &signal)
+}
+
+/// 调整 xcontrol 自身日志的级别过滤，返回之前的级别
+fn set_own_log_level(level: LogLevel) -> Result<LogLevel, String> {
+    let handle = LOG_FILTER.get().ok_or("日志尚未初始化")?;
+    let previous = handle
+        .clone_current()
+        .map(LogLevel::from)
+        .unwrap_or_default();
+    handle
+        .reload(LevelFilter::from(level))
+        .map_err(|e| format!("调整日志级别失败: {}", e))?;
+    Ok(previous)
+}
+
+/// 运行时调整日志级别：未指定 target 时为 xcontrol 自身，
+/// 否则按服务配置的 log_level_control 请求接口或发送信号，服务需正在运行
+#[tauri::command]
+async fn set_log_level(
+    target: Option<String>,
+    level: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<(), String> {
+    let level = LogLevel::parse(&level)?;
+    let Some(name) = target else {
+        let previous = set_own_log_level(level)?;
+        info!(
+            "xcontrol 日志级别: {} -> {}",
+            previous.as_str(),
+            level.as_str()
+        );
+        emit_service_event(
+            &app,
+            ServiceEvent::LogLevelChanged,
+            LogLevelChangedData {
+                service_name: None,
+                level,
+            },
+        );
+        return Ok(());
+    };
+
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    let control = config
+        .services
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("未找到服务: {}", name))?
+        .log_level_control
+        .clone()
+        .filter(|control| control.url.is_some() || control.signal.is_some())
+        .ok_or_else(|| format!("{} 服务未配置 log_level_control", name))?;
+    let pid = {
+        let manager = process_manager.lock().unwrap();
+        manager
+            .get(&name)
+            .and_then(|info| info.pid)
+            .ok_or_else(|| format!("{} 服务未运行", name))?
+    };
+
+    if let Some(url) = &control.url {
+        log_level::request(url, level)
+            .await
+            .map_err(|e| format!("{} 服务{}", name, e))?;
+    }
+    if let Some(signal) = &control.signal {
+        signal::send_named(pid, signal)?;
+    }
+    info!("{} 服务日志级别已调整为 {}", name, level.as_str());
+    emit_service_event(
+        &app,
+        ServiceEvent::LogLevelChanged,
+        LogLevelChangedData {
+            service_name: Some(name),
+            level,
+        },
+    );
+    Ok(())
+}
+
+/// 向配置了 pipe_stdin 的服务写入标准输入，数据原样写入，需要换行时由调用方附加
+#[tauri::command]
+async fn write_service_stdin(
+    name: String,
+    data: String,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<(), String> {
+    let stdin = {
+        let manager = process_manager.lock().unwrap();
+        let info = manager
+            .get(&name)
+            .ok_or_else(|| format!("未找到服务: {}", name))?;
+        info.stdin
+            .clone()
+            .ok_or_else(|| format!("{} 服务未运行或未启用 pipe_stdin", name))?
+    };
+    // 管道写满时会阻塞，不占用异步运行时线程
+    async_runtime::spawn_blocking(move || {
+        let mut stdin = stdin.lock().unwrap();
+        stdin.write_all(data.as_bytes()).and_then(|_| stdin.flush())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("写入 {} 服务标准输入失败: {}", name, e))
+}
+
+/// 请求服务进入排空模式，不停止服务；需在配置中设置 drain
+#[tauri::command]
+async fn drain_service(
+    name: String,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<(), String> {
+    let config = load_services_config().map_err(|e| format!("加载配置文件失败: {}", e))?;
+    let drain = config
+        .services
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("未找到服务: {}", name))?
+        .drain
+        .clone()
+        .ok_or_else(|| format!("{} 服务未配置 drain", name))?;
+    if !is_service_running(&process_manager, &name) {
+        return Err(format!("{} 服务未运行", name));
+    }
+    drain_running_service(&app, &name, &drain).await
+}
+
+/// 暂停指定服务（未指定时为全部服务）的进程监视，用于维护期间手动停止服务
+#[tauri::command]
+fn pause_monitoring(
+    name: Option<String>,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<Vec<String>, String> {
+    set_monitoring_paused(&app, &process_manager, name, true)
+}
+
+/// 恢复指定服务（未指定时为全部服务）的进程监视
+#[tauri::command]
+fn resume_monitoring(
+    name: Option<String>,
+    app: AppHandle<Wry>,
+    process_manager: State<'_, ProcessManager>,
+) -> Result<Vec<String>, String> {
+    set_monitoring_paused(&app, &process_manager, name, false)
+}
+
+/// 获取当前进程的权限信息
+#[tauri::command]
+fn get_privilege_info() -> PrivilegeInfo {
+    privilege::privilege_info()
+}
+
+/// 获取所有服务的当前状态
+#[tauri::command]
+fn get_health_summary(process_manager: State<'_, ProcessManager>) -> HealthSummary {
+    collect_health_summary(&process_manager)
+}
+
+#[tauri::command]
+fn get_services_status(process_manager: State<'_, ProcessManager>) -> Vec<ServiceStatus> {
+    collect_services_status(&process_manager)
+}
+
+/// 获取指定服务最近缓存的日志
+#[tauri::command]
+fn get_service_logs(name: String, log_store: State<'_, LogStore>) -> Vec<LogLine> {
+    log_store.recent(&name).unwrap_or_default()
+}
+
+/// 应用退出时的清理函数 - 修改为使用进程名而不是PID
+fn cleanup_on_exit(app_handle: &AppHandle<Wry>, process_manager: ProcessManager) {
+    info!("应用正在退出，执行清理操作...");
+
+    // 使用作用域锁，避免长时间持有锁；未启动或启动失败的服务无需终止，独立运行的服务保留
+    let services: Vec<String> = {
+        let manager = process_manager.lock().unwrap();
+        for (name, info) in manager.iter() {
+            if info.detached && info.state.has_process() {
+                info!("{} 服务独立运行 (PID: {:?})，退出时保留", name, info.pid);
+            }
+        }
+        manager
+            .iter()
+            .filter(|(_, info)| info.state.has_process() && !info.detached)
+            .map(|(name, _)| name.clone())
+            .collect()
+    };
+
+    // 各服务并行终止，超时短的服务不必等待慢服务
+    std::thread::scope(|scope| {
+        for service_name in &services {
+            let process_manager = &process_manager;
+            scope.spawn(move || {
+                stop_service_blocking(
+                    app_handle,
+                    process_manager,
+                    service_name,
+                    StopReason::Shutdown,
+                )
+            });
+        }
+    });
+
+    info!("清理操作完成");
+}
+
+/// 终止服务并更新状态（阻塞调用）
+fn stop_service_blocking(
+    emitter: &impl EventEmitter,
+    process_manager: &ProcessManager,
+    service_name: &str,
+    reason: StopReason,
+) {
+    let info = {
+        let manager = process_manager.lock().unwrap();
+        match manager.get(service_name) {
+            Some(info) => info.clone(),
+            None => return,
+        }
+    };
+
+    let _ = transition_service_state(
+        emitter,
+        process_manager,
+        service_name,
+        ServiceState::Stopping,
+    );
+    stop_service_processes(service_name, &info);
+    let _ = transition_service_state(
+        emitter,
+        process_manager,
+        service_name,
+        ServiceState::Stopped,
+    );
+    if let Some(info) = process_manager.lock().unwrap().get_mut(service_name) {
+        info.pid = None;
+        info.stdin = None;
+        info.started_at_ms = None;
+    }
+    persist::remove_record(service_name);
+    emit_service_event(
+        emitter,
+        ServiceEvent::Stopped,
+        ServiceStoppedData {
+            service_name: service_name.to_string(),
+            reason,
+        },
+    );
+}
+
+/// 查找并终止单个服务的所有进程
+fn stop_service_processes(service_name: &str, info: &ServiceInfo) {
+    info!("正在查找并终止 {} 服务的所有进程...", service_name);
+
+    // 被终止的进程，返回前等待其真正退出
+    let mut stopped = Vec::new();
+
+    // 使用进程名查找所有相关进程并终止
+    match get_processes_by_name(&info.executable, info.process_match) {
+        Ok(pids) => {
+            if pids.is_empty() {
+                info!("未找到 {} 服务的运行进程", service_name);
+            } else {
+                let timeout = Duration::from_millis(info.kill_timeout_ms);
+                let stop_signal = info.stop_signal;
+                for pid in &pids {
+                    info!("正在终止 {} 服务进程 (PID: {})...", service_name, pid);
+                }
+                process_backend::terminate_all_gracefully(&PROCESSES, &pids, timeout, stop_signal);
+                stopped.extend_from_slice(&pids);
+            }
+        }
+        Err(e) => {
+            warn!("查找 {} 服务进程时出错: {}", service_name, e);
+        }
+    }
+
+    // 终止仍留在服务 Job 中的子进程
+    job::close(service_name);
+
+    // 服务派生的、不在 Job 中的辅助进程按配置的模式查找并终止
+    for pattern in &info.cleanup_patterns {
+        match get_processes_by_pattern(pattern) {
+            Ok(pids) => {
+                for pid in pids {
+                    info!(
+                        "正在终止 {} 服务的辅助进程 (PID: {}，匹配 {})...",
+                        service_name, pid, pattern
+                    );
+                    PROCESSES.kill_by_pid(pid);
+                    stopped.push(pid);
+                }
+            }
+            Err(e) => warn!("查找 {} 服务的辅助进程时出错: {}", service_name, e),
+        }
+    }
+
+    process_backend::wait_for_killed(&PROCESSES, &stopped, &format!("停止 {} 服务", service_name));
+}
+
+/// 退出时执行的清理，按配置的 cleanup_window_classes_on_exit 与 cleanup_on_exit 设置；
+/// 配置加载前为 None，只注销 Chromium 窗口类
+static EXIT_CLEANUP: Mutex<Option<Vec<CleanupAction>>> = Mutex::new(None);
+
+/// 退出清理的最长等待时间，超时后不再等待，直接退出
+const EXIT_CLEANUP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 在单独的线程中执行退出清理，失败或超时只记录，不阻塞退出
+fn run_exit_cleanup_bounded() {
+    let actions = EXIT_CLEANUP
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| vec![CleanupAction::chromium_window_class()]);
+    if actions.is_empty() {
+        info!("未配置退出清理，跳过");
+        return;
+    }
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        // 相对路径已在加载配置时解析
+        cleanup::run_all(&actions, Path::new(""), "退出清理");
+        let _ = sender.send(());
+    });
+    if receiver.recv_timeout(EXIT_CLEANUP_TIMEOUT).is_err() {
+        warn!(
+            "退出清理超过 {}ms 未完成，跳过",
+            EXIT_CLEANUP_TIMEOUT.as_millis()
+        );
+    }
+}
+
+/// 聚焦并显示现有窗口
+fn focus_existing_window(app_handle: &AppHandle<Wry>) {
+    // 启动窗口仍在时主窗口尚未就绪，聚焦启动窗口
+    if let Some(splash) = app_handle.get_webview_window("splash") {
+        let _ = splash.set_focus();
+        info!("启动尚未完成，已聚焦到启动窗口");
+        return;
+    }
+
+    let window = match app_handle.get_webview_window(main_window_label()) {
+        Some(window) => window,
+        // 窗口已被销毁但应用仍在运行时按配置重新创建
+        None => match recreate_main_window(app_handle) {
+            Some(window) => window,
+            None => return,
+        },
+    };
+
+    // 隐藏到托盘的窗口可能已从任务栏移除，需要先恢复任务栏图标
+    let _ = window.set_skip_taskbar(false);
+    let _ = window.show();
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+    info!("已聚焦到现有的应用窗口");
+}
+
+/// 按 tauri.conf.json 中的配置重新创建主窗口
+fn recreate_main_window(app_handle: &AppHandle<Wry>) -> Option<WebviewWindow> {
+    let config = app_handle
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window| window.label == main_window_label())?
+        .clone();
+    match WebviewWindowBuilder::from_config(app_handle, &config).and_then(|builder| builder.build())
+    {
+        Ok(window) => {
+            info!("主窗口已销毁，已重新创建");
+            Some(window)
+        }
+        Err(e) => {
+            error!("重新创建主窗口失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 安全退出应用
+fn safe_exit(app_handle: AppHandle<Wry>, process_manager: ProcessManager, exit_code: i32) {
+    shutdown_app(app_handle, process_manager, exit_code, None);
+}
+
+/// 重启 xcontrol：清理方式与正常退出相同，退出前以原有参数重新启动当前可执行文件
+#[tauri::command]
+fn restart_app(app: AppHandle<Wry>, process_manager: State<'_, ProcessManager>) {
+    info!("收到重启请求");
+    let relaunch = Relaunch {
+        args: std::env::args_os().skip(1).collect(),
+        cwd: None,
+    };
+    shutdown_app(
+        app,
+        process_manager.inner().clone(),
+        EXIT_OK,
+        Some(relaunch),
+    );
+}
+
+/// 退出前启动的新实例的命令行参数（不含程序路径）与工作目录，工作目录为 None 时沿用当前目录
+struct Relaunch {
+    args: Vec<std::ffi::OsString>,
+    cwd: Option<PathBuf>,
+}
+
+/// 检测到重复启动时的处理方式，按配置的 duplicate_instance_action 设置；配置加载前为 focus
+static DUPLICATE_INSTANCE_ACTION: OnceLock<DuplicateInstanceAction> = OnceLock::new();
+
+/// 单实例插件在已运行的实例中回调，args 与 cwd 为重复启动的新进程的命令行与工作目录
+fn handle_duplicate_instance(app: &AppHandle<Wry>, args: Vec<String>, cwd: String) {
+    match DUPLICATE_INSTANCE_ACTION.get().copied().unwrap_or_default() {
+        DuplicateInstanceAction::Focus => {
+            info!("检测到重复启动，聚焦到现有窗口");
             focus_existing_window(app);
-        }))
+        }
+        DuplicateInstanceAction::Ignore => info!("检测到重复启动，已按配置忽略"),
+        DuplicateInstanceAction::Replace => {
+            info!("检测到重复启动，停止服务后以新的参数重新启动");
+            let args: Vec<String> = args.into_iter().skip(1).collect();
+            emit_service_event(
+                app,
+                ServiceEvent::InstanceReplacing,
+                InstanceReplacingData { args: args.clone() },
+            );
+            let relaunch = Relaunch {
+                args: args.into_iter().map(Into::into).collect(),
+                cwd: Some(PathBuf::from(cwd)),
+            };
+            let process_manager = app.state::<ProcessManager>().inner().clone();
+            shutdown_app(app.clone(), process_manager, EXIT_OK, Some(relaunch));
+        }
+    }
+}
+
+/// 重启后的新实例通过此环境变量得知旧实例的 PID
+const RESTART_WAIT_PID_ENV: &str = "XCONTROL_RESTART_WAIT_PID";
+
+/// 新实例等待旧实例退出的最长时间
+const PREVIOUS_INSTANCE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 以给定的参数和工作目录启动新实例，失败时只记录
+fn relaunch_self(relaunch: Relaunch) {
+    let result = std::env::current_exe().and_then(|exe| {
+        let mut cmd = Command::new(exe);
+        cmd.args(relaunch.args)
+            .env(RESTART_WAIT_PID_ENV, std::process::id().to_string());
+        if let Some(cwd) = relaunch.cwd {
+            cmd.current_dir(cwd);
+        }
+        cmd.spawn()
+    });
+    match result {
+        Ok(child) => info!("已启动新实例，PID: {}", child.id()),
+        Err(e) => error!("重新启动 xcontrol 失败: {}", e),
+    }
+}
+
+/// 由 restart_app 或 duplicate_instance_action: "replace" 启动时先等待旧实例完全退出，
+/// 否则单实例插件会检测到尚未退出的旧实例，新实例只聚焦旧窗口后退出
+fn wait_for_previous_instance() {
+    let Some(pid) = std::env::var(RESTART_WAIT_PID_ENV)
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+    else {
+        return;
+    };
+    // 不传递给服务进程
+    std::env::remove_var(RESTART_WAIT_PID_ENV);
+    info!("等待旧实例 (PID: {}) 退出...", pid);
+    if !process_backend::wait_for_exit(&PROCESSES, &[pid], PREVIOUS_INSTANCE_TIMEOUT).is_empty() {
+        warn!(
+            "旧实例在 {:?} 内仍未退出，继续启动",
+            PREVIOUS_INSTANCE_TIMEOUT
+        );
+    }
+}
+
+/// 隐藏窗口并在后台线程清理服务后退出，给定 relaunch 时退出前启动新实例
+fn shutdown_app(
+    app_handle: AppHandle<Wry>,
+    process_manager: ProcessManager,
+    exit_code: i32,
+    relaunch: Option<Relaunch>,
+) {
+    // 1. 立即隐藏窗口
+    if let Some(window) = app_handle.get_webview_window(main_window_label()) {
+        let _ = window.hide();
+    }
+
+    // 2. 在后台线程执行清理
+    std::thread::spawn(move || {
+        info!("开始后台清理...");
+
+        // 执行进程清理
+        cleanup_on_exit(&app_handle, process_manager);
+
+        // 清理窗口类、锁文件等会阻止下次启动的资源
+        run_exit_cleanup_bounded();
+
+        info!("清理完成，正在退出应用...");
+
+        // 新实例会等待本进程退出后才初始化单实例检测
+        if let Some(relaunch) = relaunch {
+            relaunch_self(relaunch);
+        }
+
+        // 延迟退出，确保资源完全释放
+        std::thread::sleep(Duration::from_millis(500));
+
+        // 强制退出（避免Tauri的清理钩子冲突）
+        std::process::exit(exit_code);
+    });
+}
+
+/// 调试构建下拦截 Ctrl+C，先清理服务进程再退出，避免留下孤儿进程
+/// 发布构建使用 windows_subsystem = "windows"，没有控制台，因此不安装
+#[cfg(debug_assertions)]
+fn install_ctrlc_handler(app_handle: AppHandle<Wry>, process_manager: ProcessManager) {
+    async_runtime::spawn(async move {
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => {
+                info!("收到 Ctrl+C，正在清理服务后退出...");
+                safe_exit(app_handle, process_manager, EXIT_OK);
+            }
+            Err(e) => error!("安装 Ctrl+C 处理器失败: {}", e),
+        }
+    });
+}
+
+/// xcontrol 自身日志的级别过滤，set_log_level 通过它在运行时调整级别
+static LOG_FILTER: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// 初始化 xcontrol 自身的日志，输出到 stderr，默认级别为 info；服务输出不经过这里
+fn init_logging() {
+    let (filter, handle) = reload::Layer::new(LevelFilter::from(LogLevel::default()));
+    let output = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_target(false);
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(output)
+        .try_init()
+        .is_ok()
+    {
+        let _ = LOG_FILTER.set(handle);
+    }
+}
+
+fn main() {
+    let options = CliOptions::from_args();
+    if options.uses_console() {
+        cli::attach_parent_console();
+    }
+    init_logging();
+    // 在 Tauri 初始化之前处理，不会打开窗口
+    if options.help {
+        cli::print_help();
+        std::process::exit(EXIT_OK);
+    }
+    if options.version {
+        cli::print_version();
+        std::process::exit(EXIT_OK);
+    }
+    if options.dump_schema {
+        match serde_json::to_string_pretty(&config::json_schema()) {
+            Ok(schema) => println!("{}", schema),
+            Err(e) => eprintln!("生成 JSON Schema 失败: {}", e),
+        }
+        std::process::exit(EXIT_OK);
+    }
+    if options.list {
+        std::process::exit(run_list());
+    }
+    if options.dry_run {
+        std::process::exit(run_dry_run());
+    }
+    DETACH_ALL.store(options.detach, std::sync::atomic::Ordering::Relaxed);
+    wait_for_previous_instance();
+
+    // 创建进程管理器
+    let process_manager: ProcessManager = Arc::new(Mutex::new(IndexMap::new()));
+    let cleanup_manager = process_manager.clone();
+    let log_store = LogBuffer::new();
+    let (frontend_ready_tx, frontend_ready_rx) = watch::channel(false);
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(
+            handle_duplicate_instance,
+        ))
+        .manage(process_manager.clone())
+        .manage(log_store.clone())
+        .manage(FrontendReady(frontend_ready_tx))
+        .manage(ServiceLockStore::new(ServiceLocks::default()))
+        .invoke_handler(tauri::generate_handler![
+            frontend_ready,
+            reload_service,
+            export_diagnostics,
+            dump_state,
+            get_privilege_info,
+            check_updates,
+            drain_service,
+            pause_monitoring,
+            signal_service,
+            set_log_level,
+            write_service_stdin,
+            resume_monitoring,
+            stop_service,
+            start_service,
+            start_service_with_overrides,
+            touch_service,
+            start_group,
+            stop_group,
+            restart_group,
+            restart_unhealthy,
+            restart_app,
+            get_restart_stats,
+            reset_restart_stats,
+            reset_restart_circuit,
+            get_launch_command,
+            recheck_service,
+            apply_updates,
+            get_services_status,
+            get_health_summary,
+            get_service_logs
+        ])
         .setup(move |app| {
-            let main_window = app.get_webview_window("main").expect("找不到主窗口");
+            // 事件通过 AppHandle 发送给所有窗口，主窗口缺失时服务照常启动
+            let app_handle = app.handle().clone();
+
+            #[cfg(debug_assertions)]
+            install_ctrlc_handler(app.handle().clone(), process_manager.clone());
+
+            async_runtime::spawn(emit_heartbeats(app_handle.clone(), process_manager.clone()));
+            async_runtime::spawn(stop_idle_services(
+                app_handle.clone(),
+                process_manager.clone(),
+            ));
+            // 文件监视只在调试构建中显式传入 --watch 时启用，发布构建不会因文件变化重启服务
+            if options.watch {
+                #[cfg(debug_assertions)]
+                async_runtime::spawn(watch_service_files(
+                    app_handle.clone(),
+                    process_manager.clone(),
+                ));
+                #[cfg(not(debug_assertions))]
+                warn!("--watch 仅在调试构建中可用，已忽略");
+            }
+
+            if options.verify_only {
+                // 校验模式：不展示窗口，启动并检查完所有服务后按结果退出
+                let process_manager = process_manager.clone();
+                let log_store = log_store.clone();
+                let frontend_ready = frontend_ready_rx.clone();
+                async_runtime::spawn(async move {
+                    let exit_code = start_all_services_and_notify(
+                        app_handle.clone(),
+                        process_manager.clone(),
+                        log_store,
+                        frontend_ready,
+                        false,
+                    )
+                    .await;
+                    print_startup_report(&process_manager);
+                    safe_exit(app_handle, process_manager, exit_code);
+                });
+                return Ok(());
+            }
+
+            if options.detach {
+                // 交接模式：关键服务全部就绪后退出并保留服务；启动失败时照常清理全部服务
+                let process_manager = process_manager.clone();
+                let log_store = log_store.clone();
+                let frontend_ready = frontend_ready_rx.clone();
+                async_runtime::spawn(async move {
+                    let exit_code = start_all_services_and_notify(
+                        app_handle.clone(),
+                        process_manager.clone(),
+                        log_store,
+                        frontend_ready,
+                        false,
+                    )
+                    .await;
+                    print_startup_report(&process_manager);
+                    if exit_code == EXIT_OK {
+                        info!("关键服务均已就绪，xcontrol 退出，服务继续独立运行");
+                    } else {
+                        warn!("启动未成功，停止所有服务后退出");
+                        for info in process_manager.lock().unwrap().values_mut() {
+                            info.detached = false;
+                        }
+                    }
+                    safe_exit(app_handle, process_manager, exit_code);
+                });
+                return Ok(());
+            }
 
             // 启动所有服务
             async_runtime::spawn(start_all_services_and_notify(
-                main_window,
+                app_handle,
                 process_manager.clone(),
+                log_store.clone(),
+                frontend_ready_rx.clone(),
+                true,
             ));
 
             Ok(())
@@ -584,20 +4435,20 @@ fn main() {
         // Tauri 2.3.0 要求 on_window_event 闭包接收 (window, event) 两个参数
         .on_window_event(move |window, event| match event {
             WindowEvent::CloseRequested { api, .. } => {
-                println!("收到窗口关闭请求");
+                info!("收到窗口关闭请求");
 
                 // 阻止默认关闭行为
                 api.prevent_close();
 
                 // 获取AppHandle并安全退出
                 let app_handle = window.app_handle().clone();
-                safe_exit(app_handle, cleanup_manager.clone());
+                safe_exit(app_handle, cleanup_manager.clone(), EXIT_OK);
 
                 // 立即隐藏窗口（提升用户体验）
                 let _ = window.hide();
             }
             WindowEvent::Destroyed => {
-                println!("窗口 {} 已销毁", window.label());
+                info!("窗口 {} 已销毁", window.label());
             }
             // 非穷尽变体必须加 ..
             _ => {}
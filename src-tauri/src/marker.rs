@@ -0,0 +1,57 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{error, info};
+
+/// 就绪标记文件：服务启动完成后在工作目录中创建该文件
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ReadyFileConfig {
+    pub path: String, // 相对路径以服务工作目录为基准
+    #[serde(default)]
+    pub non_empty: bool, // 要求文件内容非空
+    #[serde(default)]
+    pub contains: Option<String>, // 要求文件内容包含指定字符串
+    #[serde(default = "default_remove_stale")]
+    pub remove_stale: bool, // 启动前删除上次运行遗留的标记文件，默认为 true
+}
+
+fn default_remove_stale() -> bool {
+    true
+}
+
+impl ReadyFileConfig {
+    pub fn resolve(&self, working_dir: &Path) -> PathBuf {
+        working_dir.join(&self.path)
+    }
+
+    /// 标记文件存在且满足内容要求时视为就绪
+    pub fn is_ready(&self, working_dir: &Path) -> bool {
+        let path = self.resolve(working_dir);
+        if !self.non_empty && self.contains.is_none() {
+            return path.is_file();
+        }
+
+        // 服务可能正在写入，读取失败按未就绪处理
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return false;
+        };
+        if self.non_empty && content.trim().is_empty() {
+            return false;
+        }
+        self.contains
+            .as_ref()
+            .is_none_or(|expected| content.contains(expected.as_str()))
+    }
+
+    /// 删除遗留的标记文件，避免把上次运行的标记误判为本次已就绪
+    pub fn remove_stale(&self, working_dir: &Path) {
+        let path = self.resolve(working_dir);
+        if !path.exists() {
+            return;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => info!("已删除遗留的就绪标记文件: {:?}", path),
+            Err(e) => error!("删除遗留的就绪标记文件 {:?} 失败: {}", path, e),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt::Write;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// 单个服务的监控指标，由管理服务器的 /metrics 输出
+pub struct ServiceMetrics {
+    pub service_name: String,
+    pub up: bool,                  // 服务处于 healthy 状态
+    pub restarts_total: u64,       // 重启统计中的重新启动次数，reset_restart_stats 后清零
+    pub memory_bytes: Option<u64>, // 主进程常驻内存，进程未运行或无法读取时不输出
+}
+
+/// 按 Prometheus 文本格式输出指标
+pub fn render(services: &[ServiceMetrics]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# HELP xcontrol_service_up 服务是否处于 healthy 状态");
+    let _ = writeln!(out, "# TYPE xcontrol_service_up gauge");
+    for service in services {
+        let _ = writeln!(
+            out,
+            "xcontrol_service_up{{service=\"{}\"}} {}",
+            escape_label(&service.service_name),
+            u8::from(service.up)
+        );
+    }
+    let _ = writeln!(
+        out,
+        "# HELP xcontrol_service_restarts_total 服务重新启动的次数"
+    );
+    let _ = writeln!(out, "# TYPE xcontrol_service_restarts_total counter");
+    for service in services {
+        let _ = writeln!(
+            out,
+            "xcontrol_service_restarts_total{{service=\"{}\"}} {}",
+            escape_label(&service.service_name),
+            service.restarts_total
+        );
+    }
+    let _ = writeln!(
+        out,
+        "# HELP xcontrol_service_memory_bytes 服务主进程的常驻内存"
+    );
+    let _ = writeln!(out, "# TYPE xcontrol_service_memory_bytes gauge");
+    for service in services {
+        if let Some(bytes) = service.memory_bytes {
+            let _ = writeln!(
+                out,
+                "xcontrol_service_memory_bytes{{service=\"{}\"}} {}",
+                escape_label(&service.service_name),
+                bytes
+            );
+        }
+    }
+    out
+}
+
+/// 转义标签值中的反斜杠、双引号与换行
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// 读取进程的常驻内存（Windows 为工作集），进程不存在或无法读取时返回 None
+pub fn resident_memory_bytes(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        false,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+    system.process(pid).map(|process| process.memory())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_outputs_prometheus_text() {
+        let text = render(&[
+            ServiceMetrics {
+                service_name: "api".to_string(),
+                up: true,
+                restarts_total: 2,
+                memory_bytes: Some(4096),
+            },
+            ServiceMetrics {
+                service_name: "we\"b".to_string(),
+                up: false,
+                restarts_total: 0,
+                memory_bytes: None,
+            },
+        ]);
+        assert!(text.contains("# TYPE xcontrol_service_up gauge\n"));
+        assert!(text.contains("xcontrol_service_up{service=\"api\"} 1\n"));
+        assert!(text.contains("xcontrol_service_up{service=\"we\\\"b\"} 0\n"));
+        assert!(text.contains("xcontrol_service_restarts_total{service=\"api\"} 2\n"));
+        assert!(text.contains("xcontrol_service_memory_bytes{service=\"api\"} 4096\n"));
+        assert!(!text.contains("xcontrol_service_memory_bytes{service=\"we"));
+    }
+
+    #[test]
+    fn reads_own_resident_memory() {
+        assert!(resident_memory_bytes(std::process::id()).is_some_and(|bytes| bytes > 0));
+    }
+}
@@ -0,0 +1,83 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::duration;
+use crate::tcp_probe;
+
+/// 启动服务前等待网络可用，避免开机过早启动时依赖外部主机的服务失败
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct NetworkWaitConfig {
+    pub target: String, // 主机名:端口（TCP 连接）或 http(s):// 地址（收到任意响应即可）
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_network_timeout",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub timeout_ms: u64, // 超时后不再等待，照常启动服务
+    #[schemars(schema_with = "duration::ms_schema")]
+    #[serde(
+        default = "default_network_retry_interval",
+        deserialize_with = "duration::deserialize_ms"
+    )]
+    pub retry_interval_ms: u64,
+}
+
+fn default_network_timeout() -> u64 {
+    60_000
+}
+fn default_network_retry_interval() -> u64 {
+    2000
+}
+
+/// 单次 HTTP 探测的超时
+const HTTP_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn is_url(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
+
+/// target 是否为可探测的形式
+pub fn is_valid_target(target: &str) -> bool {
+    is_url(target) || tcp_probe::is_valid_address(target)
+}
+
+/// 探测一次网络是否可用：URL 收到任意 HTTP 响应（包括错误状态码）、主机名:端口 能建立 TCP 连接即视为可用
+pub async fn probe(target: &str) -> Result<(), String> {
+    if is_url(target) {
+        let client = reqwest::Client::builder()
+            .timeout(HTTP_PROBE_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        client
+            .get(target)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    } else {
+        tcp_probe::probe(target, tcp_probe::CONNECT_TIMEOUT)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn targets_are_urls_or_addresses() {
+        assert!(is_valid_target("https://example.com/"));
+        assert!(is_valid_target("8.8.8.8:53"));
+        assert!(!is_valid_target("example.com"));
+    }
+
+    #[tokio::test]
+    async fn reachable_address_is_available() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = listener.local_addr().unwrap().to_string();
+        assert!(probe(&target).await.is_ok());
+    }
+}
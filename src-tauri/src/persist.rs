@@ -0,0 +1,86 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::error;
+use xcontrol_lib::restart::RestartStats;
+
+/// 状态文件的读改写需串行，服务可能并行启动或停止
+static STATE_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 上次启动的服务进程记录，xcontrol 异常退出后重新启动时用于接管仍在运行的服务
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ServiceRecord {
+    pub pid: u32,
+    pub started_at_ms: u64,
+}
+
+fn state_file_path() -> PathBuf {
+    std::env::temp_dir().join("xcontrol-services.json")
+}
+
+fn restart_stats_path() -> PathBuf {
+    std::env::temp_dir().join("xcontrol-restart-stats.json")
+}
+
+fn read_map<T: DeserializeOwned>(path: PathBuf) -> HashMap<String, T> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_map<T: Serialize>(path: PathBuf, map: &HashMap<String, T>) {
+    let result = serde_json::to_string_pretty(map)
+        .map_err(|e| e.to_string())
+        .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        error!("写入状态文件 {:?} 失败: {}", path, e);
+    }
+}
+
+fn read_records() -> HashMap<String, ServiceRecord> {
+    read_map(state_file_path())
+}
+
+fn write_records(records: &HashMap<String, ServiceRecord>) {
+    write_map(state_file_path(), records);
+}
+
+/// 读取上次记录的服务进程
+pub fn load_record(service_name: &str) -> Option<ServiceRecord> {
+    let _guard = STATE_FILE_LOCK.lock().unwrap();
+    read_records().remove(service_name)
+}
+
+/// 记录服务进程，服务启动成功后调用
+pub fn save_record(service_name: &str, record: ServiceRecord) {
+    let _guard = STATE_FILE_LOCK.lock().unwrap();
+    let mut records = read_records();
+    records.insert(service_name.to_string(), record);
+    write_records(&records);
+}
+
+/// 删除服务进程记录，服务被正常停止后调用
+pub fn remove_record(service_name: &str) {
+    let _guard = STATE_FILE_LOCK.lock().unwrap();
+    let mut records = read_records();
+    if records.remove(service_name).is_some() {
+        write_records(&records);
+    }
+}
+
+/// 读取上次保存的重启统计，配置了 persist_restart_stats 时使用
+pub fn load_restart_stats(service_name: &str) -> Option<RestartStats> {
+    let _guard = STATE_FILE_LOCK.lock().unwrap();
+    read_map(restart_stats_path()).remove(service_name)
+}
+
+/// 保存服务的重启统计
+pub fn save_restart_stats(service_name: &str, stats: &RestartStats) {
+    let _guard = STATE_FILE_LOCK.lock().unwrap();
+    let mut all: HashMap<String, RestartStats> = read_map(restart_stats_path());
+    all.insert(service_name.to_string(), stats.clone());
+    write_map(restart_stats_path(), &all);
+}
@@ -0,0 +1,141 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use sysinfo::{Disks, MemoryRefreshKind, System};
+use tracing::warn;
+
+/// 启动前检查的系统资源下限，可在顶层设置默认值，服务中的同名字段优先
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct Preconditions {
+    #[serde(default)]
+    pub min_free_disk_bytes: Option<u64>, // 服务工作目录所在磁盘的最小可用空间
+    #[serde(default)]
+    pub min_free_memory_bytes: Option<u64>, // 系统最小可用内存
+    #[serde(default)]
+    pub abort_startup: Option<bool>, // 不满足时中止整个启动，默认只跳过该服务
+}
+
+impl Preconditions {
+    /// 合并顶层与服务的设置，服务中设置的字段覆盖顶层
+    pub fn merged(global: Option<&Preconditions>, service: Option<&Preconditions>) -> Self {
+        let global = global.cloned().unwrap_or_default();
+        let service = service.cloned().unwrap_or_default();
+        Self {
+            min_free_disk_bytes: service.min_free_disk_bytes.or(global.min_free_disk_bytes),
+            min_free_memory_bytes: service
+                .min_free_memory_bytes
+                .or(global.min_free_memory_bytes),
+            abort_startup: service.abort_startup.or(global.abort_startup),
+        }
+    }
+
+    pub fn aborts_startup(&self) -> bool {
+        self.abort_startup.unwrap_or(false)
+    }
+
+    /// 检查服务工作目录所在磁盘与系统内存，返回未满足时的原因；无法读取的资源不检查
+    pub fn check(&self, working_dir: &Path) -> Result<(), String> {
+        let free_disk = self
+            .min_free_disk_bytes
+            .and_then(|_| free_disk_bytes(working_dir));
+        let free_memory = self
+            .min_free_memory_bytes
+            .and_then(|_| available_memory_bytes());
+        self.evaluate(free_disk, free_memory)
+    }
+
+    fn evaluate(&self, free_disk: Option<u64>, free_memory: Option<u64>) -> Result<(), String> {
+        if let Some(min) = self.min_free_disk_bytes {
+            match free_disk {
+                Some(free) if free < min => {
+                    return Err(format!(
+                        "磁盘可用空间不足: 需要至少 {} 字节，当前 {} 字节",
+                        min, free
+                    ))
+                }
+                Some(_) => {}
+                None => warn!("无法读取磁盘可用空间，跳过 min_free_disk_bytes 检查"),
+            }
+        }
+        if let Some(min) = self.min_free_memory_bytes {
+            match free_memory {
+                Some(free) if free < min => {
+                    return Err(format!(
+                        "可用内存不足: 需要至少 {} 字节，当前 {} 字节",
+                        min, free
+                    ))
+                }
+                Some(_) => {}
+                None => warn!("无法读取可用内存，跳过 min_free_memory_bytes 检查"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 读取 path 所在磁盘的可用空间：取挂载点为 path 最长前缀的磁盘
+pub fn free_disk_bytes(path: &Path) -> Option<u64> {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// 读取系统可用物理内存，无法读取时返回 None
+pub fn available_memory_bytes() -> Option<u64> {
+    let mut system = System::new();
+    system.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
+    Some(system.available_memory()).filter(|&bytes| bytes > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_fields_override_global_defaults() {
+        let global = Preconditions {
+            min_free_disk_bytes: Some(100),
+            min_free_memory_bytes: Some(200),
+            abort_startup: Some(true),
+        };
+        let service = Preconditions {
+            min_free_disk_bytes: Some(50),
+            ..Default::default()
+        };
+        let merged = Preconditions::merged(Some(&global), Some(&service));
+        assert_eq!(merged.min_free_disk_bytes, Some(50));
+        assert_eq!(merged.min_free_memory_bytes, Some(200));
+        assert!(merged.aborts_startup());
+        assert!(!Preconditions::merged(None, None).aborts_startup());
+    }
+
+    #[test]
+    fn unmet_limits_are_reported_and_unknown_values_pass() {
+        let preconditions = Preconditions {
+            min_free_disk_bytes: Some(1_000),
+            min_free_memory_bytes: Some(2_000),
+            abort_startup: None,
+        };
+        assert!(preconditions.evaluate(Some(1_000), Some(2_000)).is_ok());
+        assert!(preconditions
+            .evaluate(Some(999), Some(2_000))
+            .unwrap_err()
+            .contains("磁盘"));
+        assert!(preconditions
+            .evaluate(Some(1_000), Some(1_999))
+            .unwrap_err()
+            .contains("内存"));
+        assert!(preconditions.evaluate(None, None).is_ok());
+    }
+
+    #[test]
+    fn reads_free_disk_and_memory() {
+        assert!(free_disk_bytes(&std::env::temp_dir()).is_some());
+        assert!(available_memory_bytes().is_some_and(|bytes| bytes > 0));
+    }
+}
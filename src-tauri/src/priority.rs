@@ -0,0 +1,76 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 服务进程的调度优先级，启动后立即设置
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessPriority {
+    Idle,
+    BelowNormal,
+    #[default]
+    Normal,
+    AboveNormal,
+    High,
+}
+
+impl ProcessPriority {
+    /// 对应的 nice 值
+    #[cfg(unix)]
+    fn nice(self) -> i32 {
+        match self {
+            ProcessPriority::Idle => 19,
+            ProcessPriority::BelowNormal => 10,
+            ProcessPriority::Normal => 0,
+            ProcessPriority::AboveNormal => -5,
+            ProcessPriority::High => -10,
+        }
+    }
+
+    #[cfg(windows)]
+    fn priority_class(self) -> u32 {
+        use winapi::um::winbase::{
+            ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS,
+            IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+        };
+        match self {
+            ProcessPriority::Idle => IDLE_PRIORITY_CLASS,
+            ProcessPriority::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+            ProcessPriority::Normal => NORMAL_PRIORITY_CLASS,
+            ProcessPriority::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+            ProcessPriority::High => HIGH_PRIORITY_CLASS,
+        }
+    }
+}
+
+/// 设置进程优先级（Windows 为 SetPriorityClass）
+#[cfg(windows)]
+pub fn apply(pid: u32, priority: ProcessPriority) -> Result<(), String> {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{OpenProcess, SetPriorityClass};
+    use winapi::um::winnt::PROCESS_SET_INFORMATION;
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        let ok = SetPriorityClass(handle, priority.priority_class());
+        let error = std::io::Error::last_os_error();
+        CloseHandle(handle);
+        if ok == 0 {
+            return Err(error.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 设置进程的 nice 值；提高优先级（负值）需要 root 权限
+#[cfg(unix)]
+pub fn apply(pid: u32, priority: ProcessPriority) -> Result<(), String> {
+    let result =
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, priority.nice()) };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+}
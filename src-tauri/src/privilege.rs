@@ -0,0 +1,103 @@
+use serde::Serialize;
+
+/// 当前进程的权限信息
+#[derive(Serialize, Clone)]
+pub struct PrivilegeInfo {
+    pub elevated: bool,
+}
+
+/// 当前进程能否终止指定进程
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAccess {
+    Allowed,
+    AccessDenied,
+    NotFound,
+}
+
+pub fn privilege_info() -> PrivilegeInfo {
+    PrivilegeInfo {
+        elevated: is_elevated(),
+    }
+}
+
+/// 当前进程是否以管理员权限（提升的令牌）运行
+#[cfg(windows)]
+pub fn is_elevated() -> bool {
+    use std::ptr::null_mut;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
+    use winapi::um::securitybaseapi::GetTokenInformation;
+    use winapi::um::winnt::{TokenElevation, HANDLE, TOKEN_ELEVATION, TOKEN_QUERY};
+
+    unsafe {
+        let mut token: HANDLE = null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+
+        let mut elevation: TOKEN_ELEVATION = std::mem::zeroed();
+        let mut size = 0;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut _ as *mut _,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        );
+        CloseHandle(token);
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
+
+#[cfg(not(windows))]
+pub fn is_elevated() -> bool {
+    std::process::Command::new("id")
+        .arg("-u")
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "0")
+}
+
+/// 检查当前令牌能否终止指定进程
+#[cfg(windows)]
+pub fn check_process_access(pid: u32) -> ProcessAccess {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::winnt::PROCESS_TERMINATE;
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if handle.is_null() {
+            return last_open_process_error();
+        }
+        CloseHandle(handle);
+        ProcessAccess::Allowed
+    }
+}
+
+/// 将 OpenProcess 失败后的 GetLastError 转换为访问结果
+#[cfg(windows)]
+pub fn last_open_process_error() -> ProcessAccess {
+    use winapi::shared::winerror::ERROR_ACCESS_DENIED;
+    use winapi::um::errhandlingapi::GetLastError;
+
+    match unsafe { GetLastError() } {
+        ERROR_ACCESS_DENIED => ProcessAccess::AccessDenied,
+        // 进程不存在时 OpenProcess 返回 ERROR_INVALID_PARAMETER
+        _ => ProcessAccess::NotFound,
+    }
+}
+
+#[cfg(not(windows))]
+pub fn check_process_access(pid: u32) -> ProcessAccess {
+    // kill -0 只检查权限，不发送信号
+    match std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .output()
+    {
+        Ok(output) if output.status.success() => ProcessAccess::Allowed,
+        Ok(output) if String::from_utf8_lossy(&output.stderr).contains("not permitted") => {
+            ProcessAccess::AccessDenied
+        }
+        _ => ProcessAccess::NotFound,
+    }
+}
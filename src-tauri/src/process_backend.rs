@@ -0,0 +1,303 @@
+use std::error::Error;
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::process_match::ProcessMatch;
+use crate::signal::StopSignal;
+
+pub type BackendError = Box<dyn Error + Send + Sync>;
+
+/// 新创建的进程；模拟后端不会真正创建进程，child 为 None
+pub struct SpawnedChild {
+    pub pid: u32,
+    pub child: Option<Child>,
+}
+
+/// 服务管理使用的进程操作：正式运行时为基于系统命令与 API 的实现，测试时替换为内存中的模拟实现
+pub trait ProcessBackend: Send + Sync {
+    /// 按已配置好的 Command 创建进程
+    fn spawn(&self, command: &mut Command) -> std::io::Result<SpawnedChild>;
+    /// 强制终止进程
+    fn kill_by_pid(&self, pid: u32);
+    /// 请求进程正常退出，返回请求是否发送成功
+    fn request_exit(&self, pid: u32, stop_signal: StopSignal) -> bool;
+    /// 映像名与 process_name 匹配的所有进程
+    fn list_by_name(
+        &self,
+        process_name: &str,
+        process_match: ProcessMatch,
+    ) -> Result<Vec<u32>, BackendError>;
+    fn is_alive(&self, pid: u32) -> bool;
+}
+
+/// 等待进程退出时的轮询间隔
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// 强制终止后等待进程真正退出的最长时间
+pub const KILL_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 轮询等待进程全部退出，返回超时后仍在运行的进程
+pub fn wait_for_exit(backend: &dyn ProcessBackend, pids: &[u32], timeout: Duration) -> Vec<u32> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let alive: Vec<u32> = pids
+            .iter()
+            .copied()
+            .filter(|&pid| backend.is_alive(pid))
+            .collect();
+        if alive.is_empty() || Instant::now() >= deadline {
+            return alive;
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+    }
+}
+
+/// 强制终止后等待进程退出，超时只记录仍在运行的进程
+pub fn wait_for_killed(backend: &dyn ProcessBackend, pids: &[u32], context: &str) {
+    let alive = wait_for_exit(backend, pids, KILL_WAIT_TIMEOUT);
+    if !alive.is_empty() {
+        warn!(
+            "{}: 进程 {:?} 在 {:?} 内仍未退出",
+            context, alive, KILL_WAIT_TIMEOUT
+        );
+    }
+}
+
+/// 请求进程正常退出，超时后强制终止
+pub fn terminate_gracefully(
+    backend: &dyn ProcessBackend,
+    pid: u32,
+    timeout: Duration,
+    stop_signal: StopSignal,
+) {
+    if backend.request_exit(pid, stop_signal) {
+        if wait_for_exit(backend, &[pid], timeout).is_empty() {
+            info!("进程 {} 已正常退出", pid);
+            return;
+        }
+        warn!("进程 {} 在 {:?} 内未退出，强制终止", pid, timeout);
+    }
+
+    backend.kill_by_pid(pid);
+}
+
+/// 并行地正常停止多个进程，每个进程各自计算超时
+pub fn terminate_all_gracefully(
+    backend: &dyn ProcessBackend,
+    pids: &[u32],
+    timeout: Duration,
+    stop_signal: StopSignal,
+) {
+    std::thread::scope(|scope| {
+        for &pid in pids {
+            scope.spawn(move || terminate_gracefully(backend, pid, timeout, stop_signal));
+        }
+    });
+}
+
+/// 检查并杀死指定名称的进程，返回终止的进程数量
+pub fn kill_existing(
+    backend: &dyn ProcessBackend,
+    process_name: &str,
+    process_match: ProcessMatch,
+) -> Result<usize, BackendError> {
+    info!("正在检查系统中是否存在 {} 进程...", process_name);
+
+    let pids = backend.list_by_name(process_name, process_match)?;
+
+    if pids.is_empty() {
+        info!("未发现运行中的 {} 进程", process_name);
+    } else {
+        for pid in &pids {
+            info!("发现已存在的 {} 进程，PID: {}", process_name, pid);
+            backend.kill_by_pid(*pid);
+        }
+        // 等待进程完全终止，避免新进程与旧进程争用端口或文件
+        wait_for_killed(
+            backend,
+            &pids,
+            &format!("清理已存在的 {} 进程", process_name),
+        );
+    }
+
+    Ok(pids.len())
+}
+
+/// 内存中的模拟进程后端，可模拟 PID 分配、进程崩溃与缓慢退出
+#[cfg(test)]
+pub mod fake {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    struct FakeProcess {
+        image_name: String,
+        killed: bool,
+        crashed: bool,
+        exit_delay: Option<Duration>, // 收到退出请求后多久退出，None 表示忽略退出请求
+        exits_at: Option<Instant>,
+    }
+
+    impl FakeProcess {
+        fn is_alive(&self) -> bool {
+            !self.killed && !self.crashed && self.exits_at.is_none_or(|at| Instant::now() < at)
+        }
+    }
+
+    #[derive(Default)]
+    pub struct FakeProcessBackend {
+        processes: Mutex<BTreeMap<u32, FakeProcess>>,
+    }
+
+    impl FakeProcessBackend {
+        /// 模拟一个已在运行、收到退出请求后立即退出的进程，返回其 PID
+        pub fn add(&self, image_name: &str) -> u32 {
+            let mut processes = self.processes.lock().unwrap();
+            let pid = processes.keys().next_back().map_or(1000, |pid| pid + 1);
+            processes.insert(
+                pid,
+                FakeProcess {
+                    image_name: image_name.to_string(),
+                    killed: false,
+                    crashed: false,
+                    exit_delay: Some(Duration::ZERO),
+                    exits_at: None,
+                },
+            );
+            pid
+        }
+
+        pub fn set_exit_delay(&self, pid: u32, exit_delay: Option<Duration>) {
+            if let Some(process) = self.processes.lock().unwrap().get_mut(&pid) {
+                process.exit_delay = exit_delay;
+            }
+        }
+
+        /// 模拟进程意外退出
+        pub fn crash(&self, pid: u32) {
+            if let Some(process) = self.processes.lock().unwrap().get_mut(&pid) {
+                process.crashed = true;
+            }
+        }
+
+        pub fn was_killed(&self, pid: u32) -> bool {
+            self.processes
+                .lock()
+                .unwrap()
+                .get(&pid)
+                .is_some_and(|process| process.killed)
+        }
+    }
+
+    impl ProcessBackend for FakeProcessBackend {
+        fn spawn(&self, command: &mut Command) -> std::io::Result<SpawnedChild> {
+            let program = command.get_program();
+            let image_name = Path::new(program)
+                .file_name()
+                .unwrap_or(program)
+                .to_string_lossy();
+            Ok(SpawnedChild {
+                pid: self.add(&image_name),
+                child: None,
+            })
+        }
+
+        fn kill_by_pid(&self, pid: u32) {
+            if let Some(process) = self.processes.lock().unwrap().get_mut(&pid) {
+                process.killed = true;
+            }
+        }
+
+        fn request_exit(&self, pid: u32, _stop_signal: StopSignal) -> bool {
+            let mut processes = self.processes.lock().unwrap();
+            let Some(process) = processes.get_mut(&pid).filter(|p| p.is_alive()) else {
+                return false;
+            };
+            if let Some(delay) = process.exit_delay {
+                process.exits_at.get_or_insert(Instant::now() + delay);
+            }
+            true
+        }
+
+        fn list_by_name(
+            &self,
+            process_name: &str,
+            process_match: ProcessMatch,
+        ) -> Result<Vec<u32>, BackendError> {
+            let processes = self.processes.lock().unwrap();
+            Ok(processes
+                .iter()
+                .filter(|(_, p)| p.is_alive() && process_match.matches(&p.image_name, process_name))
+                .map(|(pid, _)| *pid)
+                .collect())
+        }
+
+        fn is_alive(&self, pid: u32) -> bool {
+            self.processes
+                .lock()
+                .unwrap()
+                .get(&pid)
+                .is_some_and(FakeProcess::is_alive)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::FakeProcessBackend;
+    use super::*;
+
+    #[test]
+    fn spawned_processes_are_found_and_killed_by_name() {
+        let backend = FakeProcessBackend::default();
+        let first = backend
+            .spawn(&mut Command::new("bin/worker.exe"))
+            .unwrap()
+            .pid;
+        let second = backend.add("Worker.exe");
+        let other = backend.add("api.exe");
+        assert_ne!(first, second);
+
+        let killed = kill_existing(&backend, "worker.exe", ProcessMatch::Exact).unwrap();
+        assert_eq!(killed, 2);
+        assert!(!backend.is_alive(first) && !backend.is_alive(second));
+        assert!(backend.is_alive(other));
+        assert_eq!(
+            kill_existing(&backend, "worker.exe", ProcessMatch::Exact).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn slow_shutdown_is_waited_for_and_ignored_requests_are_killed() {
+        let backend = FakeProcessBackend::default();
+        let slow = backend.add("slow.exe");
+        backend.set_exit_delay(slow, Some(Duration::from_millis(150)));
+        let stubborn = backend.add("stubborn.exe");
+        backend.set_exit_delay(stubborn, None);
+
+        terminate_all_gracefully(
+            &backend,
+            &[slow, stubborn],
+            Duration::from_millis(500),
+            StopSignal::default(),
+        );
+        assert!(!backend.is_alive(slow) && !backend.was_killed(slow));
+        assert!(!backend.is_alive(stubborn) && backend.was_killed(stubborn));
+    }
+
+    #[test]
+    fn crashed_process_is_no_longer_alive() {
+        let backend = FakeProcessBackend::default();
+        let pid = backend.add("api.exe");
+        assert!(wait_for_exit(&backend, &[pid], Duration::ZERO) == [pid]);
+        backend.crash(pid);
+        assert!(wait_for_exit(&backend, &[pid], Duration::from_secs(1)).is_empty());
+        assert!(!backend.request_exit(pid, StopSignal::default()));
+        assert!(backend
+            .list_by_name("api.exe", ProcessMatch::Exact)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 按进程名查找服务进程时的匹配方式，比较对象为进程映像名（不区分大小写）
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessMatch {
+    /// 映像名与 executable 完全相同
+    #[default]
+    Exact,
+    /// 映像名以 executable 开头
+    Prefix,
+    /// 映像名包含 executable
+    Contains,
+}
+
+impl ProcessMatch {
+    pub fn matches(self, image_name: &str, process_name: &str) -> bool {
+        let image_name = image_name.to_lowercase();
+        let process_name = process_name.to_lowercase();
+        match self {
+            ProcessMatch::Exact => image_name == process_name,
+            ProcessMatch::Prefix => image_name.starts_with(&process_name),
+            ProcessMatch::Contains => image_name.contains(&process_name),
+        }
+    }
+
+    /// tasklist 的 IMAGENAME 过滤条件，用于缩小输出范围；包含匹配无法用过滤条件表达
+    pub fn tasklist_filter(self, process_name: &str) -> Option<String> {
+        match self {
+            ProcessMatch::Exact => Some(format!("IMAGENAME eq {}", process_name)),
+            ProcessMatch::Prefix => Some(format!("IMAGENAME eq {}*", process_name)),
+            ProcessMatch::Contains => None,
+        }
+    }
+}
+
+/// cleanup_patterns 中的模式至少包含的非通配符字符数，避免 `*.exe` 这类过宽的模式误杀进程
+pub const MIN_PATTERN_LITERALS: usize = 3;
+
+/// 模式是否足够具体，扩展名 .exe 不计入非通配符字符
+pub fn is_specific_pattern(pattern: &str) -> bool {
+    let lowercase = pattern.to_lowercase();
+    let stem = lowercase.strip_suffix(".exe").unwrap_or(&lowercase);
+    stem.chars().filter(|c| !matches!(c, '*' | '?')).count() >= MIN_PATTERN_LITERALS
+}
+
+/// 按通配符模式匹配进程映像名（不区分大小写）：`*` 匹配任意个字符，`?` 匹配单个字符
+pub fn glob_matches(pattern: &str, image_name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = image_name.to_lowercase().chars().collect();
+
+    // 回溯到最近一个 * 重新匹配
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_worker_names() {
+        assert!(glob_matches(
+            "myservice-worker-*.exe",
+            "myservice-worker-1.exe"
+        ));
+        assert!(glob_matches(
+            "myservice-worker-*.exe",
+            "MyService-Worker-12.EXE"
+        ));
+        assert!(glob_matches("worker-?.exe", "worker-3.exe"));
+        assert!(!glob_matches("worker-?.exe", "worker-12.exe"));
+        assert!(!glob_matches("myservice-worker-*.exe", "myservice.exe"));
+        assert!(!glob_matches(
+            "myservice-worker-*.exe",
+            "other-myservice-worker-1.exe"
+        ));
+        assert!(glob_matches("*helper*", "a-helper-b.exe"));
+    }
+
+    #[test]
+    fn broad_patterns_are_rejected() {
+        assert!(!is_specific_pattern("*"));
+        assert!(!is_specific_pattern("*.?"));
+        assert!(!is_specific_pattern("*.exe"));
+        assert!(!is_specific_pattern("a*.EXE"));
+        assert!(is_specific_pattern("app*.exe"));
+    }
+}
@@ -0,0 +1,86 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::logs::LogLine;
+
+/// ready_pattern 的最大长度
+pub const MAX_PATTERN_LEN: usize = 1024;
+
+/// 编译后正则表达式的内存上限，避免 `(a{1000}){1000}` 这类模式占用大量内存
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// 每行只匹配开头的这么多字节；regex 的匹配耗时与输入长度成线性关系，超长的行不会拖慢检查
+const MAX_MATCH_LINE_LEN: usize = 16 * 1024;
+
+/// 编译就绪日志的正则表达式，模式为空、过长或编译失败时返回错误说明
+pub fn compile(pattern: &str) -> Result<Regex, String> {
+    if pattern.is_empty() {
+        return Err("ready_pattern 不能为空".to_string());
+    }
+    if pattern.len() > MAX_PATTERN_LEN {
+        return Err(format!("ready_pattern 不能超过 {} 个字节", MAX_PATTERN_LEN));
+    }
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// 是否为服务在 since_ms 之后输出的、匹配就绪模式的行
+pub fn is_ready_line(regex: &Regex, line: &LogLine, service_name: &str, since_ms: u64) -> bool {
+    if line.service_name != service_name || line.timestamp_ms < since_ms {
+        return false;
+    }
+    let mut end = line.line.len().min(MAX_MATCH_LINE_LEN);
+    while !line.line.is_char_boundary(end) {
+        end -= 1;
+    }
+    regex.is_match(&line.line[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(service_name: &str, text: &str, timestamp_ms: u64) -> LogLine {
+        LogLine::new(service_name, "stdout", text.to_string(), timestamp_ms)
+    }
+
+    #[test]
+    fn compile_rejects_empty_invalid_and_oversized_patterns() {
+        assert!(compile("listening on :\\d+").is_ok());
+        assert!(compile("").is_err());
+        assert!(compile("(unclosed").is_err());
+        assert!(compile(&"a".repeat(MAX_PATTERN_LEN + 1)).is_err());
+        assert!(compile("(a{1000}){1000}").is_err());
+    }
+
+    #[test]
+    fn ready_line_matches_only_new_output_of_the_service() {
+        let regex = compile("Server listening on :\\d+").unwrap();
+        assert!(is_ready_line(
+            &regex,
+            &line("api", "Server listening on :8080", 200),
+            "api",
+            100
+        ));
+        assert!(!is_ready_line(
+            &regex,
+            &line("api", "Server listening on :8080", 50),
+            "api",
+            100
+        ));
+        assert!(!is_ready_line(
+            &regex,
+            &line("web", "Server listening on :8080", 200),
+            "api",
+            100
+        ));
+        assert!(!is_ready_line(
+            &regex,
+            &line("api", "Starting server", 200),
+            "api",
+            100
+        ));
+    }
+}
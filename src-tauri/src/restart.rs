@@ -0,0 +1,262 @@
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::config::ServiceConfig;
+
+/// 距上次重启超过该时间则视为非连续重启，退避清零
+const RESTART_BACKOFF_RESET: Duration = Duration::from_secs(60);
+/// 第一次连续重启前的等待时间，之后逐次加倍
+const RESTART_BACKOFF_BASE: Duration = Duration::from_millis(1000);
+/// 退避等待时间上限
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// 重启退避：短时间内反复重启同一服务时逐次加倍等待时间，防止重启风暴
+#[derive(Default, Clone)]
+pub struct RestartBackoff {
+    consecutive: u32,
+    last_restart: Option<Instant>,
+}
+
+impl RestartBackoff {
+    /// 记录一次重启，返回重启前应等待的时间
+    pub fn next_delay(&mut self) -> Duration {
+        let now = Instant::now();
+        let recent = self
+            .last_restart
+            .is_some_and(|last| now.duration_since(last) < RESTART_BACKOFF_RESET);
+        self.consecutive = if recent { self.consecutive + 1 } else { 0 };
+        self.last_restart = Some(now);
+
+        if self.consecutive == 0 {
+            return Duration::ZERO;
+        }
+        let factor = 1u32 << (self.consecutive - 1).min(5);
+        RESTART_BACKOFF_BASE
+            .saturating_mul(factor)
+            .min(RESTART_BACKOFF_MAX)
+    }
+}
+
+/// 只输出连续重启次数，Instant 无法序列化
+impl Serialize for RestartBackoff {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("RestartBackoff", 1)?;
+        state.serialize_field("consecutive", &self.consecutive)?;
+        state.end()
+    }
+}
+
+/// 崩溃自动重启的熔断器：时间窗口内自动重启次数达到上限后不再自动重启，直到手动重启
+#[derive(Default, Clone)]
+pub struct RestartCircuit {
+    restarts: VecDeque<Instant>,
+    open: bool,
+}
+
+impl RestartCircuit {
+    /// 允许自动重启时记录本次重启并返回 true；已熔断或刚达到上限时返回 false
+    pub fn try_restart(&mut self, limit: u32, window: Duration) -> bool {
+        self.try_restart_at(Instant::now(), limit, window)
+    }
+
+    fn try_restart_at(&mut self, now: Instant, limit: u32, window: Duration) -> bool {
+        if self.open {
+            return false;
+        }
+        while self
+            .restarts
+            .front()
+            .is_some_and(|&restart| now.duration_since(restart) >= window)
+        {
+            self.restarts.pop_front();
+        }
+        if self.restarts.len() >= limit as usize {
+            self.open = true;
+            return false;
+        }
+        self.restarts.push_back(now);
+        true
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// 时间窗口内已记录的自动重启次数（含已过期但尚未清理的记录）
+    pub fn recent_restarts(&self) -> usize {
+        self.restarts.len()
+    }
+
+    /// 手动重启后清空记录并恢复自动重启
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Serialize for RestartCircuit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("RestartCircuit", 2)?;
+        state.serialize_field("recent_restarts", &self.recent_restarts())?;
+        state.serialize_field("open", &self.open)?;
+        state.end()
+    }
+}
+
+/// 服务重新启动的统计，重新加载配置后保留，可通过 reset_restart_stats 清零
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct RestartStats {
+    pub count: u64,                   // 首次启动之后再次启动的次数
+    pub last_restart_ms: Option<u64>, // 最近一次重新启动的时间
+}
+
+impl RestartStats {
+    /// 记录一次重新启动
+    pub fn record(&mut self, now_ms: u64) {
+        self.count += 1;
+        self.last_restart_ms = Some(now_ms);
+    }
+}
+
+/// 列出直接或间接依赖 name 的所有服务，依赖者总排在它所依赖的服务之后；
+/// 无法满足依赖顺序（存在循环依赖）的服务按配置文件顺序追加在最后
+pub fn dependents_in_order(services: &[ServiceConfig], name: &str) -> Vec<String> {
+    let mut dependents: HashSet<&str> = HashSet::new();
+    let mut pending = vec![name];
+    while let Some(current) = pending.pop() {
+        for service in services {
+            if service.depends_on.iter().any(|dep| dep == current)
+                && service.name != name
+                && dependents.insert(&service.name)
+            {
+                pending.push(&service.name);
+            }
+        }
+    }
+
+    let mut ordered: Vec<String> = Vec::new();
+    let mut remaining: Vec<&ServiceConfig> = services
+        .iter()
+        .filter(|service| dependents.contains(service.name.as_str()))
+        .collect();
+
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|service| {
+            service.depends_on.iter().all(|dep| {
+                !dependents.contains(dep.as_str()) || ordered.iter().any(|done| done == dep)
+            })
+        });
+        match ready {
+            Some(index) => ordered.push(remaining.remove(index).name.clone()),
+            None => {
+                warn!("检测到循环依赖，剩余服务按配置顺序重启");
+                ordered.extend(remaining.drain(..).map(|service| service.name.clone()));
+            }
+        }
+    }
+
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn services(dependencies: &[(&str, &[&str])]) -> Vec<ServiceConfig> {
+        dependencies
+            .iter()
+            .map(|(name, depends_on)| {
+                serde_json::from_value(serde_json::json!({
+                    "name": name,
+                    "executable": name,
+                    "working_dir": ".",
+                    "depends_on": depends_on,
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dependents_come_after_their_dependencies() {
+        let services = services(&[
+            ("web", &["api", "cache"]),
+            ("api", &["db"]),
+            ("cache", &[]),
+            ("db", &[]),
+        ]);
+        assert_eq!(dependents_in_order(&services, "db"), ["api", "web"]);
+        assert_eq!(dependents_in_order(&services, "cache"), ["web"]);
+        assert!(dependents_in_order(&services, "web").is_empty());
+    }
+
+    #[test]
+    fn cyclic_dependencies_are_appended_in_config_order() {
+        let services = services(&[
+            ("worker", &["db", "scheduler"]),
+            ("scheduler", &["worker"]),
+            ("api", &["db"]),
+            ("db", &[]),
+        ]);
+        assert_eq!(
+            dependents_in_order(&services, "db"),
+            ["api", "worker", "scheduler"]
+        );
+    }
+
+    #[test]
+    fn circuit_opens_after_burst_limit() {
+        let window = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut circuit = RestartCircuit::default();
+        for i in 0..3 {
+            assert!(circuit.try_restart_at(start + Duration::from_secs(i), 3, window));
+        }
+        assert!(!circuit.try_restart_at(start + Duration::from_secs(3), 3, window));
+        assert!(circuit.is_open());
+        assert_eq!(
+            serde_json::to_value(&circuit).unwrap(),
+            serde_json::json!({"recent_restarts": 3, "open": true})
+        );
+        // 熔断后即使窗口期已过也保持打开，直到手动重置
+        assert!(!circuit.try_restart_at(start + Duration::from_secs(600), 3, window));
+
+        circuit.reset();
+        assert!(!circuit.is_open());
+        assert!(circuit.try_restart_at(start + Duration::from_secs(601), 3, window));
+    }
+
+    #[test]
+    fn restarts_outside_window_are_forgotten() {
+        let window = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut circuit = RestartCircuit::default();
+        for i in 0..5 {
+            assert!(circuit.try_restart_at(start + Duration::from_secs(i * 6), 2, window));
+        }
+        assert!(!circuit.is_open());
+    }
+
+    #[test]
+    fn restart_stats_count_and_remember_last_restart() {
+        let mut stats = RestartStats::default();
+        stats.record(1_000);
+        stats.record(2_000);
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.last_restart_ms, Some(2_000));
+    }
+
+    #[test]
+    fn backoff_doubles_for_consecutive_restarts() {
+        let mut backoff = RestartBackoff::default();
+        assert_eq!(backoff.next_delay(), Duration::ZERO);
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(2));
+        for _ in 0..10 {
+            backoff.next_delay();
+        }
+        assert_eq!(backoff.next_delay(), RESTART_BACKOFF_MAX);
+    }
+}
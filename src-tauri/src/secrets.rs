@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use xcontrol_lib::error::ServiceError;
+
+/// 机密引用前缀，如 `secret://db_password`
+const SECRET_PREFIX: &str = "secret://";
+
+/// 凭据管理器中的目标名称前缀，`secret://db_password` 对应 `xcontrol/db_password`
+#[cfg(windows)]
+const CREDENTIAL_TARGET_PREFIX: &str = "xcontrol/";
+
+/// 解析可能为机密引用的配置值，普通值原样返回
+///
+/// 解析结果只能用于启动进程或发送请求，不得打印或写入事件
+pub fn resolve(value: &str) -> Result<String, ServiceError> {
+    match value.strip_prefix(SECRET_PREFIX) {
+        Some(name) => {
+            read_secret(name).ok_or_else(|| ServiceError::SecretUnavailable(name.to_string()))
+        }
+        None => Ok(value.to_string()),
+    }
+}
+
+/// 逐个解析参数中的机密引用
+pub fn resolve_all(values: &[String]) -> Result<Vec<String>, ServiceError> {
+    values.iter().map(|value| resolve(value)).collect()
+}
+
+/// 逐个解析环境变量值中的机密引用，变量名不变
+pub fn resolve_env(env: &HashMap<String, String>) -> Result<HashMap<String, String>, ServiceError> {
+    env.iter()
+        .map(|(key, value)| Ok((key.clone(), resolve(value)?)))
+        .collect()
+}
+
+/// 从 Windows 凭据管理器读取普通凭据的密码
+#[cfg(windows)]
+fn read_secret(name: &str) -> Option<String> {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr::null_mut;
+    use winapi::um::wincred::{CredFree, CredReadW, CRED_TYPE_GENERIC, PCREDENTIALW};
+
+    let target: Vec<u16> = OsStr::new(&format!("{}{}", CREDENTIAL_TARGET_PREFIX, name))
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut credential: PCREDENTIALW = null_mut();
+    let found = unsafe { CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) };
+    if found == 0 {
+        return None;
+    }
+
+    let blob = unsafe {
+        let credential = &*credential;
+        std::slice::from_raw_parts(
+            credential.CredentialBlob,
+            credential.CredentialBlobSize as usize,
+        )
+        .to_vec()
+    };
+    unsafe { CredFree(credential as _) };
+
+    // cmdkey 与凭据管理器界面以 UTF-16LE 保存密码，其余工具可能写入 UTF-8
+    let utf16: Vec<u16> = blob
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    match String::from_utf16(&utf16) {
+        Ok(secret) if blob.len() % 2 == 0 => Some(secret),
+        _ => String::from_utf8(blob).ok(),
+    }
+}
+
+#[cfg(not(windows))]
+fn read_secret(_name: &str) -> Option<String> {
+    tracing::warn!("当前平台不支持 secret:// 引用");
+    None
+}
@@ -0,0 +1,65 @@
+use std::process::Command;
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+/// 拼接交给 shell 执行的完整命令行，参数按当前平台的规则加引号后追加在命令之后
+pub fn build_command_line(program: &str, args: &[String]) -> String {
+    let mut command_line = program.to_string();
+    for arg in args {
+        command_line.push(' ');
+        command_line.push_str(&quote_arg(arg));
+    }
+    command_line
+}
+
+/// 创建通过 shell 执行命令行的 Command：Windows 使用 `cmd /S /C`，其他平台使用 `sh -c`
+#[cfg(windows)]
+pub fn shell_command(command_line: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    // 使用 raw_arg 原样传递，避免 Rust 的参数转义与 cmd 的解析规则冲突
+    cmd.raw_arg(cmd_arguments(command_line));
+    cmd
+}
+
+/// 交给 cmd 的参数：/S 使 cmd 只去掉最外层的一对引号，
+/// 否则命令与参数都带引号时（如 `"C:\Program Files\a.bat" "x y"`）会被错误地去掉首尾引号
+#[cfg(windows)]
+pub fn cmd_arguments(command_line: &str) -> String {
+    format!("/S /C \"{}\"", command_line)
+}
+
+#[cfg(not(windows))]
+pub fn shell_command(command_line: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command_line);
+    cmd
+}
+
+/// 为单个参数加引号（cmd 规则）
+#[cfg(windows)]
+pub fn quote_arg(arg: &str) -> String {
+    let needs_quotes = arg.is_empty()
+        || arg
+            .chars()
+            .any(|c| c.is_whitespace() || "&|<>^()\"".contains(c));
+    if needs_quotes {
+        format!("\"{}\"", arg.replace('"', "\"\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+/// 为单个参数加引号（POSIX sh 规则）
+#[cfg(not(windows))]
+pub fn quote_arg(arg: &str) -> String {
+    let is_safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c));
+    if is_safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
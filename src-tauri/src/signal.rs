@@ -0,0 +1,106 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 正常停止时发送的信号，仅在非 Windows 平台生效
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StopSignal {
+    #[default]
+    Term,
+    Int,
+    Quit,
+    Kill,
+}
+
+#[cfg(unix)]
+impl StopSignal {
+    fn as_nix(self) -> nix::sys::signal::Signal {
+        use nix::sys::signal::Signal;
+        match self {
+            StopSignal::Term => Signal::SIGTERM,
+            StopSignal::Int => Signal::SIGINT,
+            StopSignal::Quit => Signal::SIGQUIT,
+            StopSignal::Kill => Signal::SIGKILL,
+        }
+    }
+}
+
+/// 向进程发送信号，返回是否发送成功
+#[cfg(unix)]
+pub fn send(pid: u32, signal: StopSignal) -> bool {
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    match kill(Pid::from_raw(pid as i32), signal.as_nix()) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::error!("向进程 {} 发送 {:?} 失败: {}", pid, signal, e);
+            false
+        }
+    }
+}
+
+/// 按名称向进程发送信号（如 HUP、SIGUSR1），用于 signal_service 命令
+#[cfg(unix)]
+pub fn send_named(pid: u32, name: &str) -> Result<(), String> {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+
+    let name = name.trim().to_ascii_uppercase();
+    let full_name = if name.starts_with("SIG") {
+        name
+    } else {
+        format!("SIG{}", name)
+    };
+    let signal: Signal = full_name
+        .parse()
+        .map_err(|_| format!("不支持的信号: {}", full_name))?;
+    kill(Pid::from_raw(pid as i32), signal)
+        .map_err(|e| format!("向进程 {} 发送 {} 失败: {}", pid, full_name, e))
+}
+
+/// 按名称向进程所在控制台发送控制事件（CTRL_C 或 CTRL_BREAK），用于 signal_service 命令
+///
+/// 控制事件只能发给当前进程所在的控制台，因此临时附加到服务的控制台；
+/// 同一控制台上的所有进程都会收到该事件
+#[cfg(windows)]
+pub fn send_named(pid: u32, name: &str) -> Result<(), String> {
+    use winapi::um::consoleapi::SetConsoleCtrlHandler;
+    use winapi::um::wincon::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT, CTRL_C_EVENT,
+    };
+
+    let event = match name.trim().to_ascii_uppercase().as_str() {
+        "CTRL_C" | "INT" | "SIGINT" => CTRL_C_EVENT,
+        "CTRL_BREAK" | "BREAK" => CTRL_BREAK_EVENT,
+        other => {
+            return Err(format!(
+                "Windows 仅支持 CTRL_C 与 CTRL_BREAK，不支持: {}",
+                other
+            ))
+        }
+    };
+
+    unsafe {
+        FreeConsole();
+        if AttachConsole(pid) == 0 {
+            return Err(format!(
+                "附加到进程 {} 的控制台失败: {}",
+                pid,
+                std::io::Error::last_os_error()
+            ));
+        }
+        // 附加期间忽略控制事件，避免 xcontrol 自身被中断
+        SetConsoleCtrlHandler(None, 1);
+        let sent = GenerateConsoleCtrlEvent(event, 0) != 0;
+        let error = std::io::Error::last_os_error();
+        FreeConsole();
+        // 控制事件异步投递，稍后再恢复默认处理
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        SetConsoleCtrlHandler(None, 0);
+        if !sent {
+            return Err(format!("向进程 {} 发送控制事件失败: {}", pid, error));
+        }
+    }
+    Ok(())
+}
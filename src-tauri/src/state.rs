@@ -0,0 +1,118 @@
+use serde::Serialize;
+
+/// 服务生命周期状态
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceState {
+    /// lazy 服务尚未被请求启动
+    NotStarted,
+    /// 未运行
+    Stopped,
+    /// 进程已创建，等待健康检查
+    Starting,
+    /// 健康检查通过
+    Healthy,
+    /// 进程在运行但健康检查未通过
+    Unhealthy,
+    /// 正在终止
+    Stopping,
+    /// 启动或终止失败
+    Failed,
+    /// 一次性任务已成功退出
+    Completed,
+}
+
+impl ServiceState {
+    /// 判断是否允许从当前状态迁移到 next
+    pub fn can_transition_to(self, next: ServiceState) -> bool {
+        use ServiceState::*;
+        matches!(
+            (self, next),
+            (NotStarted | Stopped | Failed | Completed, Starting)
+                | (
+                    Starting,
+                    Healthy | Unhealthy | Failed | Stopping | Completed
+                )
+                | (Healthy, Unhealthy | Stopping | Failed)
+                | (Unhealthy, Healthy | Stopping | Failed)
+                | (Stopping, Stopped | Failed)
+        )
+    }
+
+    /// 该状态下服务是否可能仍有运行中的进程
+    pub fn has_process(self) -> bool {
+        matches!(
+            self,
+            ServiceState::Starting
+                | ServiceState::Healthy
+                | ServiceState::Unhealthy
+                | ServiceState::Stopping
+        )
+    }
+}
+
+/// service_state_changed 事件的数据结构
+#[derive(Serialize, Clone)]
+pub struct StateChangedData {
+    pub service_name: String,
+    pub from: ServiceState,
+    pub to: ServiceState,
+}
+
+/// 所有服务的汇总健康状态，供外部编排系统判断整机是否就绪
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct HealthSummary {
+    pub all_ready: bool,
+    pub ready: usize,        // healthy 的服务与已完成的一次性任务
+    pub total: usize,        // 不含尚未被请求启动的 lazy 服务
+    pub failed: Vec<String>, // 处于 failed 或 unhealthy 状态的服务
+}
+
+impl HealthSummary {
+    pub fn from_states<'a>(states: impl IntoIterator<Item = (&'a str, ServiceState)>) -> Self {
+        let mut ready = 0;
+        let mut total = 0;
+        let mut failed = Vec::new();
+        for (name, state) in states {
+            match state {
+                ServiceState::NotStarted => continue,
+                ServiceState::Healthy | ServiceState::Completed => ready += 1,
+                ServiceState::Failed | ServiceState::Unhealthy => failed.push(name.to_string()),
+                _ => {}
+            }
+            total += 1;
+        }
+        Self {
+            all_ready: ready == total,
+            ready,
+            total,
+            failed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_ignores_lazy_services_and_lists_failures() {
+        let summary = HealthSummary::from_states([
+            ("api", ServiceState::Healthy),
+            ("migrate", ServiceState::Completed),
+            ("reports", ServiceState::NotStarted),
+        ]);
+        assert!(summary.all_ready);
+        assert_eq!((summary.ready, summary.total), (2, 2));
+
+        let summary = HealthSummary::from_states([
+            ("api", ServiceState::Healthy),
+            ("web", ServiceState::Starting),
+            ("worker", ServiceState::Failed),
+            ("cache", ServiceState::Unhealthy),
+        ]);
+        assert!(!summary.all_ready);
+        assert_eq!((summary.ready, summary.total), (1, 4));
+        assert_eq!(summary.failed, ["worker", "cache"]);
+    }
+}
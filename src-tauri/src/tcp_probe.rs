@@ -0,0 +1,74 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::timeout;
+
+/// 单个地址的连接超时
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 地址是否为 主机名:端口 形式（IPv6 字面量需写成 [::1]:8080）
+pub fn is_valid_address(address: &str) -> bool {
+    address
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+}
+
+/// 解析主机名并依次尝试所有解析出的地址（IPv4 与 IPv6），任一连接成功即返回该地址；
+/// 双栈环境下 localhost 可能先解析为 ::1，只连第一个地址会误判未就绪
+pub async fn probe(address: &str, connect_timeout: Duration) -> Result<SocketAddr, String> {
+    let addrs: Vec<SocketAddr> = lookup_host(address)
+        .await
+        .map_err(|e| format!("无法解析 {}: {}", address, e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("{} 未解析出任何地址", address));
+    }
+
+    let mut errors = Vec::new();
+    for addr in addrs {
+        match timeout(connect_timeout, TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => return Ok(addr),
+            Ok(Err(e)) => errors.push(format!("{}: {}", addr, e)),
+            Err(_) => errors.push(format!("{}: 连接超时", addr)),
+        }
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn address_requires_host_and_port() {
+        assert!(is_valid_address("localhost:8080"));
+        assert!(is_valid_address("127.0.0.1:8080"));
+        assert!(is_valid_address("[::1]:8080"));
+        assert!(!is_valid_address("localhost"));
+        assert!(!is_valid_address(":8080"));
+        assert!(!is_valid_address("localhost:http"));
+    }
+
+    #[tokio::test]
+    async fn localhost_connects_on_any_resolved_address() {
+        // 只监听 IPv4，localhost 先解析为 ::1 时也应回退到 127.0.0.1
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let addr = probe(&format!("localhost:{}", port), CONNECT_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(addr, listener.local_addr().unwrap());
+    }
+
+    #[tokio::test]
+    async fn closed_port_reports_every_address() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let error = probe(&addr.to_string(), CONNECT_TIMEOUT).await.unwrap_err();
+        assert!(error.contains(&addr.to_string()));
+    }
+}
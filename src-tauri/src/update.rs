@@ -0,0 +1,105 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::config::{service_executable_path, ServiceConfig};
+
+/// 服务二进制更新配置：更新程序把新版本放入暂存目录，重启时由 xcontrol 替换
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct UpdateConfig {
+    pub staging_dir: String, // 相对路径以配置文件所在目录为基准
+    #[serde(default = "default_version_file")]
+    pub version_file: String, // 暂存目录与可执行文件所在目录中的版本文件名
+}
+
+fn default_version_file() -> String {
+    "VERSION".to_string()
+}
+
+/// 单个服务的更新检查结果
+#[derive(Serialize, Clone, Debug)]
+pub struct UpdateInfo {
+    pub service_name: String,
+    pub current_version: Option<String>,
+    pub staged_version: Option<String>,
+    pub update_available: bool,
+}
+
+fn read_version(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+}
+
+fn staged_executable_path(service: &ServiceConfig, update: &UpdateConfig) -> PathBuf {
+    Path::new(&update.staging_dir).join(&service.executable)
+}
+
+fn installed_version_path(service: &ServiceConfig, update: &UpdateConfig) -> PathBuf {
+    let exe_path = service_executable_path(service);
+    exe_path
+        .parent()
+        .unwrap_or(Path::new(&service.working_dir))
+        .join(&update.version_file)
+}
+
+/// 比较暂存版本与当前版本；未配置更新或 shell 模式的服务返回 None
+pub fn check(service: &ServiceConfig) -> Option<UpdateInfo> {
+    let update = service.update_check.as_ref().filter(|_| !service.shell)?;
+    let current_version = read_version(&installed_version_path(service, update));
+    let staged_version = read_version(&Path::new(&update.staging_dir).join(&update.version_file));
+    let update_available = staged_version.is_some() && staged_version != current_version;
+
+    Some(UpdateInfo {
+        service_name: service.name.clone(),
+        current_version,
+        staged_version,
+        update_available,
+    })
+}
+
+/// 确认暂存的可执行文件存在且可执行，必须在停止服务之前调用
+pub fn verify_staged(service: &ServiceConfig) -> Result<(), String> {
+    let update = service.update_check.as_ref().ok_or("未配置 update_check")?;
+    let staged = staged_executable_path(service, update);
+    let metadata =
+        std::fs::metadata(&staged).map_err(|e| format!("暂存文件不存在 {:?}: {}", staged, e))?;
+    if !metadata.is_file() {
+        return Err(format!("暂存路径不是文件: {:?}", staged));
+    }
+    if metadata.len() == 0 {
+        return Err(format!("暂存文件为空: {:?}", staged));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!("暂存文件没有可执行权限: {:?}", staged));
+        }
+    }
+
+    Ok(())
+}
+
+/// 用暂存的可执行文件和版本文件替换当前版本，调用前服务必须已停止
+pub fn install(service: &ServiceConfig) -> Result<(), String> {
+    let update = service.update_check.as_ref().ok_or("未配置 update_check")?;
+    let staged = staged_executable_path(service, update);
+    let target = service_executable_path(service);
+    std::fs::copy(&staged, &target).map_err(|e| format!("替换 {:?} 失败: {}", target, e))?;
+
+    let staged_version = Path::new(&update.staging_dir).join(&update.version_file);
+    let installed_version = installed_version_path(service, update);
+    std::fs::copy(&staged_version, &installed_version)
+        .map_err(|e| format!("更新版本文件 {:?} 失败: {}", installed_version, e))?;
+
+    info!(
+        "{} 服务已更新为 {:?}",
+        service.name,
+        read_version(&installed_version)
+    );
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::config::resolve_relative_to;
+use crate::process_match;
+
+/// 监视目录时最多记录的文件数，避免误把大目录（如 node_modules）列入监视时过度占用资源
+const MAX_WATCHED_FILES: usize = 10_000;
+
+/// 监视路径下所有文件的修改时间，两次快照不同即表示有文件被修改、新增或删除
+pub type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+/// 记录 watch 中各路径当前的文件修改时间：相对路径以 working_dir 为基准，
+/// 文件名部分支持 `*` 与 `?` 通配符，目录递归包含其中的所有文件，不存在的路径被忽略
+pub fn snapshot(working_dir: &Path, patterns: &[String]) -> Snapshot {
+    let mut files = Snapshot::new();
+    for pattern in patterns {
+        let path = PathBuf::from(resolve_relative_to(working_dir, pattern));
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if file_name.contains(['*', '?']) {
+            let Some(dir) = path.parent() else {
+                continue;
+            };
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if process_match::glob_matches(&file_name, &name) {
+                    collect(&entry.path(), &mut files);
+                }
+            }
+        } else {
+            collect(&path, &mut files);
+        }
+    }
+    files
+}
+
+fn collect(path: &Path, files: &mut Snapshot) {
+    if files.len() >= MAX_WATCHED_FILES {
+        return;
+    }
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return;
+    };
+    if metadata.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            collect(&entry.path(), files);
+        }
+    } else if let Ok(modified) = metadata.modified() {
+        files.insert(path.to_path_buf(), modified);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn snapshot_changes_when_watched_files_change() {
+        let dir = std::env::temp_dir().join(format!("xcontrol-watch-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::fs::write(dir.join("app.exe"), "v1").unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        std::fs::write(dir.join("config").join("a.toml"), "").unwrap();
+        let patterns = [
+            "*.exe".to_string(),
+            "config".to_string(),
+            "missing".to_string(),
+        ];
+
+        let before = snapshot(&dir, &patterns);
+        assert_eq!(before.len(), 2);
+        assert_eq!(snapshot(&dir, &patterns), before);
+
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        assert_eq!(snapshot(&dir, &patterns), before);
+
+        std::fs::write(dir.join("config").join("b.toml"), "").unwrap();
+        let added = snapshot(&dir, &patterns);
+        assert_eq!(added.len(), 3);
+
+        let file = std::fs::File::options()
+            .write(true)
+            .open(dir.join("app.exe"))
+            .unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_ne!(snapshot(&dir, &patterns), added);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[cfg(windows)]
+use std::collections::HashMap;
+#[cfg(windows)]
+use std::path::Path;
+
+/// 服务进程的控制台窗口显示方式
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowMode {
+    /// 不创建控制台窗口，输出写入日志缓冲区
+    #[default]
+    Hidden,
+    /// 显示服务自己的控制台窗口
+    Visible,
+    /// 控制台窗口最小化到任务栏，不抢占焦点
+    Minimized,
+}
+
+impl WindowMode {
+    /// 是否捕获服务输出；有窗口时输出保留在服务自己的控制台中
+    pub fn captures_output(self) -> bool {
+        self == WindowMode::Hidden
+    }
+}
+
+/// 不为子进程创建控制台窗口，辅助命令（tasklist、taskkill 等）同样使用
+#[cfg(windows)]
+pub const CREATE_NO_WINDOW: u32 = winapi::um::winbase::CREATE_NO_WINDOW;
+
+/// 以最小化且不激活的控制台窗口启动进程，返回 PID
+///
+/// std 的 Command 无法设置 STARTUPINFO.wShowWindow，因此直接调用 CreateProcessW；
+/// env 为空时继承当前进程的环境变量，否则在其基础上覆盖
+#[cfg(windows)]
+pub fn spawn_minimized(
+    command_line: &str,
+    working_dir: &Path,
+    env: &HashMap<String, String>,
+) -> std::io::Result<u32> {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr::null_mut;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{CreateProcessW, PROCESS_INFORMATION, STARTUPINFOW};
+    use winapi::um::winbase::{
+        CREATE_NEW_CONSOLE, CREATE_UNICODE_ENVIRONMENT, STARTF_USESHOWWINDOW,
+    };
+    use winapi::um::winuser::SW_SHOWMINNOACTIVE;
+
+    let to_wide = |s: &OsStr| {
+        s.encode_wide()
+            .chain(std::iter::once(0))
+            .collect::<Vec<_>>()
+    };
+    // CreateProcessW 可能改写命令行缓冲区，必须是可变的
+    let mut command_line = to_wide(OsStr::new(command_line));
+    let working_dir = to_wide(working_dir.as_os_str());
+    let mut environment = (!env.is_empty()).then(|| environment_block(env));
+
+    let mut startup_info: STARTUPINFOW = unsafe { std::mem::zeroed() };
+    startup_info.cb = std::mem::size_of::<STARTUPINFOW>() as u32;
+    startup_info.dwFlags = STARTF_USESHOWWINDOW;
+    startup_info.wShowWindow = SW_SHOWMINNOACTIVE as u16;
+    let mut process_info: PROCESS_INFORMATION = unsafe { std::mem::zeroed() };
+
+    let created = unsafe {
+        CreateProcessW(
+            null_mut(),
+            command_line.as_mut_ptr(),
+            null_mut(),
+            null_mut(),
+            0,
+            CREATE_NEW_CONSOLE | CREATE_UNICODE_ENVIRONMENT,
+            environment
+                .as_mut()
+                .map_or(null_mut(), |block| block.as_mut_ptr().cast()),
+            working_dir.as_ptr(),
+            &mut startup_info,
+            &mut process_info,
+        )
+    };
+    if created == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    unsafe {
+        CloseHandle(process_info.hThread);
+        CloseHandle(process_info.hProcess);
+    }
+    Ok(process_info.dwProcessId)
+}
+
+/// 以当前进程的环境变量为基础覆盖 env 后，生成 CreateProcessW 使用的 UTF-16 环境块：
+/// 每项为以 0 结尾的 `KEY=VALUE`，按变量名（不区分大小写）排序，整体再以一个 0 结尾
+#[cfg(windows)]
+fn environment_block(env: &HashMap<String, String>) -> Vec<u16> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStrExt;
+
+    let mut vars: Vec<(OsString, OsString)> = std::env::vars_os()
+        .filter(|(key, _)| {
+            !env.keys()
+                .any(|k| key.to_string_lossy().eq_ignore_ascii_case(k))
+        })
+        .collect();
+    vars.extend(env.iter().map(|(k, v)| (k.into(), v.into())));
+    vars.sort_by_key(|(key, _)| key.to_string_lossy().to_uppercase());
+
+    let mut block = Vec::new();
+    for (key, value) in vars {
+        block.extend(key.encode_wide());
+        block.push(u16::from(b'='));
+        block.extend(value.encode_wide());
+        block.push(0);
+    }
+    block.push(0);
+    block
+}
@@ -0,0 +1,20 @@
+import { listen } from "@tauri-apps/api/event";
+
+// 启动窗口只展示进度，启动完成后由后端关闭并显示主窗口
+const status = document.getElementById("status")!;
+
+const setStatus = (text: string) => {
+  status.textContent = text;
+};
+
+listen<{ service_name: string }>("service_starting", (event) => {
+  setStatus(`正在启动 ${event.payload.service_name}...`);
+});
+
+listen<{ service_name: string }>("service_ready", (event) => {
+  setStatus(`${event.payload.service_name} 已就绪`);
+});
+
+listen<{ service_name: string; error: string }>("service_error", (event) => {
+  setStatus(`${event.payload.service_name}: ${event.payload.error}`);
+});